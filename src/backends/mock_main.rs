@@ -1,26 +1,39 @@
 mod operation;
 mod statement;
 
+use crate::error::VerifyError;
 use crate::middleware::{
     self, hash_str, AnchoredKey, Hash, MainPodInputs, NativeOperation, NativeStatement, NonePod,
-    Params, Pod, PodId, PodProver, StatementArg, ToFields, KEY_TYPE, SELF,
+    Params, Pod, PodId, PodProver, StatementArg, KEY_TYPE, NULL, SELF,
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use itertools::Itertools;
 pub use operation::*;
 use plonky2::hash::poseidon::PoseidonHash;
 use plonky2::plonk::config::Hasher;
 pub use statement::*;
 use std::any::Any;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::iter;
 
 pub const VALUE_TYPE: &str = "MockMainPOD";
 
-pub struct MockProver {}
+#[derive(Clone, Debug, Default)]
+pub struct MockProver {
+    /// Check that every operation justifies its statement while proving, instead of only when
+    /// verifying.
+    pub check_operations: bool,
+}
 
 impl PodProver for MockProver {
     fn prove(&mut self, params: &Params, inputs: MainPodInputs) -> Result<Box<dyn Pod>> {
-        Ok(Box::new(MockMainPod::new(params, inputs)?))
+        params.validate()?;
+        let pod = MockMainPod::new(params, inputs)?;
+        if self.check_operations {
+            pod.check_operations()?;
+        }
+        Ok(Box::new(pod))
     }
 }
 
@@ -32,7 +45,6 @@ pub struct MockMainPod {
     input_main_pods: Vec<Box<dyn Pod>>,
     // New statements introduced by this pod
     input_statements: Vec<Statement>,
-    public_statements: Vec<Statement>,
     operations: Vec<Operation>,
     // All statements (inherited + new)
     statements: Vec<Statement>,
@@ -105,13 +117,18 @@ fn fmt_statement_index(
     Ok(())
 }
 
-fn fill_pad<T: Clone>(v: &mut Vec<T>, pad_value: T, len: usize) {
+fn fill_pad<T: Clone>(v: &mut Vec<T>, pad_value: T, len: usize) -> Result<()> {
     if v.len() > len {
-        panic!("length exceeded");
+        return Err(anyhow!(
+            "Length {} exceeds the padded length {}.",
+            v.len(),
+            len
+        ));
     }
     while v.len() < len {
         v.push(pad_value.clone());
     }
+    Ok(())
 }
 
 impl MockMainPod {
@@ -128,19 +145,68 @@ impl MockMainPod {
     fn offset_public_statements(&self) -> usize {
         self.offset_input_statements() + self.params.max_priv_statements()
     }
-    fn pad_statement(params: &Params, s: &mut Statement) {
-        fill_pad(&mut s.1, StatementArg::None, params.max_statement_args)
+    /// Statements in the public region, the ones the id is derived from
+    fn public_statements(&self) -> &[Statement] {
+        &self.statements[self.offset_public_statements()..]
+    }
+    /// Statements introduced by this pod that aren't in the public region
+    pub fn prv_statements(&self) -> Vec<Statement> {
+        self.statements[self.offset_input_statements()..self.offset_public_statements()].to_vec()
+    }
+    /// All the laid out statements, including the input pods and padding
+    pub fn all_statements(&self) -> &[Statement] {
+        &self.statements
+    }
+    /// Operations justifying the statements introduced by this pod (private and public)
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+    /// The statement with origin=SELF replaced by origin=self.id()
+    fn replace_self(&self, statement: &Statement) -> Statement {
+        Statement(
+            statement.0,
+            statement
+                .1
+                .iter()
+                .map(|sa| match &sa {
+                    StatementArg::Key(AnchoredKey(pod_id, h)) if *pod_id == SELF => {
+                        StatementArg::Key(AnchoredKey(self.id, *h))
+                    }
+                    _ => sa.clone(),
+                })
+                .collect(),
+        )
     }
-    fn pad_operation(params: &Params, op: &mut Operation) {
-        fill_pad(&mut op.1, OperationArg::None, params.max_operation_args)
+    fn pad_statement(params: &Params, s: &mut Statement) -> Result<()> {
+        Self::pad_statement_args(params, &mut s.1)
+    }
+    fn pad_operation(params: &Params, op: &mut Operation) -> Result<()> {
+        Self::pad_operation_args(params, &mut op.1)
     }
 
-    fn layout_statements(params: &Params, inputs: &MainPodInputs) -> Vec<Statement> {
+    fn layout_statements(params: &Params, inputs: &MainPodInputs) -> Result<Vec<Statement>> {
         let mut statements = Vec::new();
+        let statement_none = Self::statement_none(params);
+        let pad = |st: Option<&middleware::Statement>| -> Result<Statement> {
+            match st {
+                Some(st) => {
+                    let mut st = st.clone().into();
+                    Self::pad_statement(params, &mut st)?;
+                    Ok(st)
+                }
+                None => Ok(statement_none.clone()),
+            }
+        };
 
         // Input signed pods region
         let none_sig_pod: Box<dyn Pod> = Box::new(NonePod {});
-        assert!(inputs.signed_pods.len() <= params.max_input_signed_pods);
+        if inputs.signed_pods.len() > params.max_input_signed_pods {
+            return Err(anyhow!(
+                "Too many input signed pods: {} exceeds max_input_signed_pods ({}).",
+                inputs.signed_pods.len(),
+                params.max_input_signed_pods
+            ));
+        }
         for i in 0..params.max_input_signed_pods {
             let pod = inputs
                 .signed_pods
@@ -148,21 +214,28 @@ impl MockMainPod {
                 .map(|p| *p)
                 .unwrap_or(&none_sig_pod);
             let sts = pod.pub_statements();
-            assert!(sts.len() <= params.max_signed_pod_values);
+            if sts.len() > params.max_signed_pod_values {
+                return Err(anyhow!(
+                    "Input signed pod {} has {} values, exceeding max_signed_pod_values ({}).",
+                    pod.id(),
+                    sts.len(),
+                    params.max_signed_pod_values
+                ));
+            }
             for j in 0..params.max_signed_pod_values {
-                let mut st = sts
-                    .get(j)
-                    .unwrap_or(&middleware::Statement::None)
-                    .clone()
-                    .into();
-                Self::pad_statement(params, &mut st);
-                statements.push(st);
+                statements.push(pad(sts.get(j))?);
             }
         }
 
         // Input main pods region
         let none_main_pod: Box<dyn Pod> = Box::new(NonePod {});
-        assert!(inputs.main_pods.len() <= params.max_input_main_pods);
+        if inputs.main_pods.len() > params.max_input_main_pods {
+            return Err(anyhow!(
+                "Too many input main pods: {} exceeds max_input_main_pods ({}).",
+                inputs.main_pods.len(),
+                params.max_input_main_pods
+            ));
+        }
         for i in 0..params.max_input_main_pods {
             let pod = inputs
                 .main_pods
@@ -170,90 +243,136 @@ impl MockMainPod {
                 .map(|p| *p)
                 .unwrap_or(&none_main_pod);
             let sts = pod.pub_statements();
-            assert!(sts.len() <= params.max_public_statements);
+            if sts.len() > params.max_public_statements {
+                return Err(anyhow!(
+                    "Input main pod {} has {} public statements, exceeding max_public_statements \
+                     ({}).",
+                    pod.id(),
+                    sts.len(),
+                    params.max_public_statements
+                ));
+            }
             for j in 0..params.max_public_statements {
-                let mut st = sts
-                    .get(j)
-                    .unwrap_or(&middleware::Statement::None)
-                    .clone()
-                    .into();
-                Self::pad_statement(params, &mut st);
-                statements.push(st);
+                statements.push(pad(sts.get(j))?);
             }
         }
 
         // Input statements
-        assert!(inputs.statements.len() <= params.max_priv_statements());
+        if inputs.statements.len() > params.max_priv_statements() {
+            return Err(anyhow!(
+                "Too many statements: {} exceeds max_statements - max_public_statements ({}).",
+                inputs.statements.len(),
+                params.max_priv_statements()
+            ));
+        }
         for i in 0..params.max_priv_statements() {
-            let mut st = inputs
-                .statements
-                .get(i)
-                .unwrap_or(&middleware::Statement::None)
-                .clone()
-                .into();
-            Self::pad_statement(params, &mut st);
-            statements.push(st);
+            statements.push(pad(inputs.statements.get(i))?);
         }
 
-        // Public statements
-        assert!(inputs.public_statements.len() < params.max_public_statements);
-        let mut type_st = middleware::Statement::ValueOf(
+        // Public statements, skipping repeated ones
+        let mut seen = HashSet::new();
+        let public_statements = inputs
+            .public_statements
+            .iter()
+            .filter(|st| seen.insert(st.commitment()))
+            .collect_vec();
+        // One public slot is taken by the pod type statement.
+        if public_statements.len() >= params.max_public_statements {
+            return Err(anyhow!(
+                "Too many public statements: {} exceeds max_public_statements - 1 ({}).",
+                public_statements.len(),
+                params.max_public_statements - 1
+            ));
+        }
+        let type_st = middleware::Statement::ValueOf(
             AnchoredKey(SELF, hash_str(KEY_TYPE)),
             middleware::Value(hash_str(VALUE_TYPE).0),
-        )
-        .into();
-        Self::pad_statement(params, &mut type_st);
-        statements.push(type_st);
+        );
+        statements.push(pad(Some(&type_st))?);
 
         for i in 0..(params.max_public_statements - 1) {
-            let mut st = inputs
-                .public_statements
-                .get(i)
-                .unwrap_or(&middleware::Statement::None)
-                .clone()
-                .into();
-            Self::pad_statement(params, &mut st);
-            statements.push(st);
+            statements.push(pad(public_statements.get(i).copied())?);
         }
 
-        statements
+        Ok(statements)
+    }
+
+    /// Lay out only the real statements, in the same order as `layout_statements` but without
+    /// the `None` padding of each region.  The result is meant for inspection and tooling; the
+    /// padded layout is the one that matches the circuit.
+    pub fn layout_statements_compact(inputs: &MainPodInputs) -> Vec<Statement> {
+        let type_st = middleware::Statement::ValueOf(
+            AnchoredKey(SELF, hash_str(KEY_TYPE)),
+            middleware::Value(hash_str(VALUE_TYPE).0),
+        );
+        let mut seen = HashSet::new();
+        let public_statements = inputs
+            .public_statements
+            .iter()
+            .filter(|st| seen.insert(st.commitment()))
+            .cloned();
+        inputs
+            .signed_pods
+            .iter()
+            .chain(inputs.main_pods.iter())
+            .flat_map(|pod| pod.pub_statements())
+            .chain(inputs.statements.iter().cloned())
+            .chain(iter::once(type_st))
+            .chain(public_statements)
+            .filter(|st| !st.is_none())
+            .map(|st| st.into())
+            .collect()
+    }
+
+    /// Index of the first occurrence of each laid out statement, to resolve operation
+    /// arguments without scanning.
+    fn index_statements(statements: &[Statement]) -> HashMap<middleware::Statement, usize> {
+        let mut index = HashMap::new();
+        for (i, st) in statements.iter().enumerate() {
+            if let Ok(st) = middleware::Statement::try_from(st.clone()) {
+                index.entry(st).or_insert(i);
+            }
+        }
+        index
     }
 
     fn find_op_arg(
-        statements: &[Statement],
+        index: &HashMap<middleware::Statement, usize>,
         op_arg: &middleware::Statement,
     ) -> Result<OperationArg, OperationArgError> {
         match op_arg {
             middleware::Statement::None => Ok(OperationArg::None),
-            _ => statements
-                .iter()
-                .enumerate()
-                .find_map(|(i, s)| {
-                    // TODO: Error handling
-                    (&middleware::Statement::try_from(s.clone()).unwrap() == op_arg).then_some(i)
-                })
-                .map(OperationArg::Index)
-                .ok_or(OperationArgError::StatementNotFound),
+            _ => index
+                .get(op_arg)
+                .map(|i| OperationArg::Index(*i))
+                .ok_or_else(|| OperationArgError::StatementNotFound(op_arg.clone())),
         }
     }
 
     fn process_private_statements_operations(
         params: &Params,
-        statements: &[Statement],
+        index: &HashMap<middleware::Statement, usize>,
         input_operations: &[middleware::Operation],
-    ) -> Result<Vec<Operation>, OperationArgError> {
+    ) -> Result<Vec<Operation>> {
         let mut operations = Vec::new();
+        let operation_none = Self::operation_none(params);
         for i in 0..params.max_priv_statements() {
-            let op = input_operations
-                .get(i)
-                .unwrap_or(&middleware::Operation::None)
-                .clone();
+            let op = match input_operations.get(i) {
+                Some(op) => op,
+                None => {
+                    operations.push(operation_none.clone());
+                    continue;
+                }
+            };
             let mid_args = op.args();
             let mut args = mid_args
                 .iter()
-                .map(|mid_arg| Self::find_op_arg(statements, mid_arg))
+                .map(|mid_arg| Self::find_op_arg(index, mid_arg))
                 .collect::<Result<Vec<_>, OperationArgError>>()?;
-            Self::pad_operation_args(params, &mut args);
+            if let Some(proof) = op.merkle_proof() {
+                args.push(OperationArg::MerkleProof(proof.clone()));
+            }
+            Self::pad_operation_args(params, &mut args)?;
             operations.push(Operation(op.code(), args));
         }
         Ok(operations)
@@ -264,39 +383,44 @@ impl MockMainPod {
     fn process_public_statements_operations(
         params: &Params,
         statements: &[Statement],
+        index: &HashMap<middleware::Statement, usize>,
         mut operations: Vec<Operation>,
-    ) -> Result<Vec<Operation>, OperationArgError> {
+    ) -> Result<Vec<Operation>> {
         let offset_public_statements = statements.len() - params.max_public_statements;
+        let operation_none = Self::operation_none(params);
         operations.push(Operation(NativeOperation::NewEntry, vec![]));
         for i in 0..(params.max_public_statements - 1) {
             let st = &statements[offset_public_statements + i + 1];
-            let mut op = if st.is_none() {
-                Operation(NativeOperation::None, vec![])
-            } else {
-                let mid_arg = st.clone();
-                Operation(
-                    NativeOperation::CopyStatement,
-                    // TODO
-                    vec![Self::find_op_arg(statements, &mid_arg.try_into().unwrap())?],
-                )
-            };
-            fill_pad(&mut op.1, OperationArg::None, params.max_operation_args);
+            if st.is_none() {
+                operations.push(operation_none.clone());
+                continue;
+            }
+            let mid_arg = st.clone().try_into()?;
+            let mut op = Operation(
+                NativeOperation::CopyStatement,
+                vec![Self::find_op_arg(index, &mid_arg)?],
+            );
+            Self::pad_operation(params, &mut op)?;
             operations.push(op);
         }
         Ok(operations)
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn new(params: &Params, inputs: MainPodInputs) -> Result<Self> {
         // TODO: Figure out a way to handle public statements.  For example, in the public slots
         // use copy operations taking the private statements that need to be public.  We may change
         // the MainPodInputs type to accommodate for that.
         // TODO: Insert a new public statement of ValueOf with `key=KEY_TYPE,
         // value=PodType::MockMainPod`
-        let statements = Self::layout_statements(params, &inputs);
+        let statements = Self::layout_statements(params, &inputs)?;
+        trace_event!(debug, statements = statements.len(), "laid out statements");
+        let index = Self::index_statements(&statements);
         let operations =
-            Self::process_private_statements_operations(params, &statements, inputs.operations)?;
+            Self::process_private_statements_operations(params, &index, inputs.operations)?;
         let operations =
-            Self::process_public_statements_operations(params, &statements, operations)?;
+            Self::process_public_statements_operations(params, &statements, &index, operations)?;
+        trace_event!(debug, operations = operations.len(), "resolved operations");
 
         let input_signed_pods = inputs
             .signed_pods
@@ -310,15 +434,14 @@ impl MockMainPod {
             .cloned()
             .map(|s| {
                 let mut s = s.into();
-                Self::pad_statement(params, &mut s);
-                s
+                Self::pad_statement(params, &mut s)?;
+                Ok(s)
             })
-            .collect_vec();
-        let public_statements =
-            statements[statements.len() - params.max_public_statements..].to_vec();
-
+            .collect::<Result<Vec<_>>>()?;
         // get the id out of the public statements
-        let id: PodId = PodId(hash_statements(&public_statements)?);
+        let id: PodId = PodId(hash_statements(
+            &statements[statements.len() - params.max_public_statements..],
+        )?);
 
         Ok(Self {
             params: params.clone(),
@@ -326,52 +449,171 @@ impl MockMainPod {
             input_signed_pods,
             input_main_pods,
             input_statements,
-            public_statements,
             statements,
             operations,
         })
     }
 
+    /// Check that each operation justifies its statement, reporting the index of the first
+    /// statement that isn't.
+    fn check_operations(&self) -> Result<()> {
+        let offset = self.offset_input_statements();
+        for (i, (st, op)) in self.statements[offset..]
+            .iter()
+            .zip(self.operations.iter())
+            .enumerate()
+        {
+            let op = op.resolve(&self.statements[..offset + i])?;
+            let st = middleware::Statement::try_from(st.clone())?;
+            if !op.check(&st)? {
+                return Err(anyhow!(
+                    "Statement {} ({}) isn't justified by operation {:?}.",
+                    offset + i,
+                    st,
+                    op
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the input pods are valid and that the statements in the input pods regions
+    /// are their public statements, so that operations can only refer to statements backed by an
+    /// input pod.
+    fn input_pods_check(&self) -> Result<(), VerifyError> {
+        let regions = [
+            (
+                &self.input_signed_pods,
+                self.offset_input_signed_pods(),
+                self.params.max_signed_pod_values,
+            ),
+            (
+                &self.input_main_pods,
+                self.offset_input_main_pods(),
+                self.params.max_public_statements,
+            ),
+        ];
+        for (pods, offset, size) in regions {
+            for (i, pod) in pods.iter().enumerate() {
+                let valid = pod.verify()
+                    && pod.pub_statements().into_iter().enumerate().all(|(j, st)| {
+                        let mut st = st.into();
+                        j < size
+                            && Self::pad_statement(&self.params, &mut st).is_ok()
+                            && self.statements.get(offset + i * size + j) == Some(&st)
+                    });
+                if !valid {
+                    return Err(VerifyError::InputPod(pod.id()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the statements copied from the input pods regions are public statements of
+    /// the input pod owning the slot.
+    fn copies_check(&self) -> Result<(), VerifyError> {
+        let offset_input_main_pods = self.offset_input_main_pods();
+        let offset_input_statements = self.offset_input_statements();
+        for (i, op) in self.operations.iter().enumerate() {
+            let j = match (op.0, op.1.first()) {
+                (NativeOperation::CopyStatement, Some(OperationArg::Index(j)))
+                    if *j < offset_input_statements =>
+                {
+                    *j
+                }
+                _ => continue,
+            };
+            let pod = if j < offset_input_main_pods {
+                self.input_signed_pods
+                    .get(j / self.params.max_signed_pod_values)
+            } else {
+                self.input_main_pods
+                    .get((j - offset_input_main_pods) / self.params.max_public_statements)
+            };
+            let st = &self.statements[j];
+            let backed = match (pod, middleware::Statement::try_from(st.clone())) {
+                (Some(pod), Ok(st)) => pod.pub_statements().contains(&st),
+                _ => false,
+            };
+            if !backed {
+                return Err(VerifyError::CopyNotInInput {
+                    index: offset_input_statements + i,
+                    statement: st.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
     fn statement_none(params: &Params) -> Statement {
-        let mut args = Vec::with_capacity(params.max_statement_args);
-        Self::pad_statement_args(&params, &mut args);
-        Statement(NativeStatement::None, args)
+        Statement(
+            NativeStatement::None,
+            vec![StatementArg::None; params.max_statement_args],
+        )
     }
 
     fn operation_none(params: &Params) -> Operation {
-        let mut op = Operation(NativeOperation::None, vec![]);
-        fill_pad(&mut op.1, OperationArg::None, params.max_operation_args);
-        op
+        Operation(
+            NativeOperation::None,
+            vec![OperationArg::None; params.max_operation_args],
+        )
     }
 
-    fn pad_statement_args(params: &Params, args: &mut Vec<StatementArg>) {
-        fill_pad(args, StatementArg::None, params.max_statement_args)
+    fn pad_statement_args(params: &Params, args: &mut Vec<StatementArg>) -> Result<()> {
+        fill_pad(args, StatementArg::None, params.max_statement_args).map_err(|_| {
+            anyhow!(
+                "{} statement arguments exceed max_statement_args ({}).",
+                args.len(),
+                params.max_statement_args
+            )
+        })
     }
 
-    fn pad_operation_args(params: &Params, args: &mut Vec<OperationArg>) {
-        fill_pad(args, OperationArg::None, params.max_operation_args)
+    fn pad_operation_args(params: &Params, args: &mut Vec<OperationArg>) -> Result<()> {
+        fill_pad(args, OperationArg::None, params.max_operation_args).map_err(|_| {
+            anyhow!(
+                "{} operation arguments exceed max_operation_args ({}).",
+                args.len(),
+                params.max_operation_args
+            )
+        })
     }
 }
 
+/// Hash of the canonical field encoding of `statements`, the one used by `Pod::to_fields` (see
+/// [middleware::canonical_statements_to_fields]).  The id of a pod is this hash over its public
+/// statements anchored at `SELF`, rather than the hash of `Pod::to_fields`, because
+/// `pub_statements` already refers to the pod by its id.
 pub fn hash_statements(statements: &[Statement]) -> Result<middleware::Hash> {
-    let field_elems = statements
-        .into_iter()
-        .flat_map(|statement| statement.clone().to_fields().0)
-        .collect::<Vec<_>>();
+    let statements = statements
+        .iter()
+        .map(|st| middleware::Statement::try_from(st.clone()))
+        .collect::<Result<Vec<_>>>()?;
+    let field_elems = middleware::canonical_statements_to_fields(&statements);
     Ok(Hash(PoseidonHash::hash_no_pad(&field_elems).elements))
 }
 
 impl Pod for MockMainPod {
     fn verify(&self) -> bool {
+        self.verify_detailed().is_ok()
+    }
+    fn verify_detailed(&self) -> Result<(), VerifyError> {
+        // verify the copies and input pods first, so that an invalid pod can't be used as a valid
+        // input
+        self.copies_check()?;
+        self.input_pods_check()?;
         let input_statement_offset = self.offset_input_statements();
         // get the input_statements from the self.statements
         let input_statements = &self.statements[input_statement_offset..];
         // get the id out of the public statements, and ensure it is equal to self.id
-        let ids_match = self.id == PodId(hash_statements(&self.public_statements).unwrap());
+        if self.id_from_content() != self.id {
+            return Err(VerifyError::IdMismatch);
+        }
         // find a ValueOf statement from the public statements with key=KEY_TYPE and check that the
         // value is PodType::MockMainPod
         let has_type_statement = self
-            .public_statements
+            .public_statements()
             .iter()
             .find(|s| {
                 s.0 == NativeStatement::ValueOf
@@ -383,6 +625,9 @@ impl Pod for MockMainPod {
                     }
             })
             .is_some();
+        if !has_type_statement {
+            return Err(VerifyError::MissingTypeStatement);
+        }
         // check that all `input_statements` of type `ValueOf` with origin=SELF have unique keys
         // (no duplicates)
         // TODO: Instead of doing this, do a uniqueness check when verifying the output of a
@@ -413,49 +658,132 @@ impl Pod for MockMainPod {
                 .collect::<Vec<_>>();
             !(0..key_id_pairs.len() - 1).any(|i| key_id_pairs[i + 1..].contains(&key_id_pairs[i]))
         };
+        if !value_ofs_unique {
+            return Err(VerifyError::DuplicateValueOf);
+        }
         // verify that all `input_statements` are correctly generated
-        // by `self.operations` (where each operation can only access previous statements)
-        let statement_check = input_statements
-            .iter()
-            .enumerate()
-            .map(|(i, s)| {
-                self.operations[i]
-                    .deref(&self.statements[..input_statement_offset + i])
-                    .unwrap()
-                    .check(&s.clone().try_into().unwrap())
-            })
-            .collect::<Result<Vec<_>>>()
-            .unwrap();
-        ids_match && has_type_statement && value_ofs_unique & statement_check.into_iter().all(|b| b)
+        // by `self.operations` (where each operation can only access previous statements).  An
+        // operation that can't be resolved or that fails to check makes the pod invalid.
+        for (i, s) in input_statements.iter().enumerate() {
+            let index = input_statement_offset + i;
+            let not_justified = |operation: String, reason: String| {
+                trace_event!(warn, index, statement = %s, %operation, %reason,
+                    "statement isn't justified by its operation");
+                Err(VerifyError::StatementNotJustified {
+                    index,
+                    statement: s.to_string(),
+                    operation,
+                    reason,
+                })
+            };
+            let op = match self.operations[i].resolve(&self.statements[..index]) {
+                Ok(op) => op,
+                Err(e) => return not_justified(self.operations[i].to_string(), e.to_string()),
+            };
+            let st = match middleware::Statement::try_from(s.clone()) {
+                Ok(st) => st,
+                Err(e) => return not_justified(format!("{:?}", op), e.to_string()),
+            };
+            match op.check(&st) {
+                Ok(true) => {}
+                Ok(false) => return not_justified(format!("{:?}", op), "check failed".into()),
+                Err(e) => return not_justified(format!("{:?}", op), e.to_string()),
+            }
+        }
+        Ok(())
     }
     fn id(&self) -> PodId {
         self.id
     }
+    /// Hash of the public statements.  Statements that can't be converted to middleware
+    /// statements give the `NULL` id.
+    fn id_from_content(&self) -> PodId {
+        PodId(hash_statements(self.public_statements()).unwrap_or(NULL))
+    }
     fn pub_statements(&self) -> Vec<middleware::Statement> {
         // return the public statements, where when origin=SELF is replaced by origin=self.id()
         self.statements
             .iter()
             .skip(self.offset_public_statements())
-            .cloned()
-            .map(|statement| {
-                Statement(
-                    statement.0.clone(),
-                    statement
-                        .1
-                        .iter()
-                        .map(|sa| match &sa {
-                            StatementArg::Key(AnchoredKey(pod_id, h)) if *pod_id == SELF => {
-                                StatementArg::Key(AnchoredKey(self.id(), *h))
-                            }
-                            _ => sa.clone(),
-                        })
-                        .collect(),
-                )
-                .try_into()
-                .unwrap()
+            .map(|statement| self.replace_self(statement).try_into().unwrap())
+            .collect()
+    }
+    /// Public statements are always copies, so the copy of a statement introduced by this pod is
+    /// replaced by the operation that justified that statement, following copies of other
+    /// public statements.  Returns an empty list if an operation can't be resolved.
+    fn pub_operations(&self) -> Vec<middleware::Operation> {
+        let statements = self
+            .statements
+            .iter()
+            .map(|statement| self.replace_self(statement))
+            .collect::<Vec<_>>();
+        let offset_input_statements = self.offset_input_statements();
+        (self.offset_public_statements()..self.statements.len())
+            .map(|mut index| {
+                while let Some(Operation(NativeOperation::CopyStatement, args)) =
+                    self.operations.get(index - offset_input_statements)
+                {
+                    match args.first() {
+                        Some(OperationArg::Index(i))
+                            if (offset_input_statements..index).contains(i) =>
+                        {
+                            index = *i
+                        }
+                        _ => break,
+                    }
+                }
+                self.operations
+                    .get(index - offset_input_statements)
+                    .ok_or(anyhow!("Missing operation for statement {}.", index))?
+                    .resolve(&statements[..index])
+            })
+            .collect::<Result<Vec<_>>>()
+            .unwrap_or_default()
+    }
+
+    /// Anchored keys referenced by the statements introduced by this pod, both private and
+    /// public, with `SELF` replaced by the pod id.
+    fn referenced_keys(&self) -> Vec<AnchoredKey> {
+        self.statements[self.offset_input_statements()..]
+            .iter()
+            .flat_map(|st| st.1.iter())
+            .filter_map(|arg| match arg {
+                StatementArg::Key(AnchoredKey(pod_id, key)) if *pod_id == SELF => {
+                    Some(AnchoredKey(self.id, *key))
+                }
+                StatementArg::Key(ak) => Some(ak.clone()),
+                _ => None,
             })
+            .sorted_by_key(|ak| (ak.0 .0, ak.1))
+            .dedup()
             .collect()
     }
+    /// GraphViz DOT rendering of the statement DAG: a node per statement, and an edge from
+    /// each argument of an operation to the statement it justifies.
+    fn to_graphviz(&self) -> Option<String> {
+        let escape = |s: String| s.replace('"', "\\\"");
+        let mut dot = format!("digraph \"{}\" {{\n", self.id);
+        for (i, st) in self.statements.iter().enumerate() {
+            if !st.is_none() {
+                dot += &format!(
+                    "  s{} [label=\"{:02}: {}\"];\n",
+                    i,
+                    i,
+                    escape(st.to_string())
+                );
+            }
+        }
+        let offset = self.offset_input_statements();
+        for (i, op) in self.operations.iter().enumerate() {
+            for arg in op.1.iter() {
+                if let OperationArg::Index(j) = arg {
+                    dot += &format!("  s{} -> s{} [label=\"{:?}\"];\n", j, offset + i, op.0);
+                }
+            }
+        }
+        dot += "}\n";
+        Some(dot)
+    }
 
     fn into_any(self: Box<Self>) -> Box<dyn Any> {
         self
@@ -470,7 +798,9 @@ pub mod tests {
         great_boy_pod_full_flow, tickets_pod_full_flow, zu_kyc_pod_builder,
         zu_kyc_sign_pod_builders,
     };
+    use crate::frontend::{self, MainPodBuilder, OperationArg};
     use crate::middleware;
+    use crate::op;
 
     #[test]
     fn test_mock_main_zu_kyc() {
@@ -485,9 +815,9 @@ pub mod tests {
             pk: "ZooDeel".into(),
         };
         let pay_stub_pod = pay_stub_builder.sign(&mut signer).unwrap();
-        let kyc_builder = zu_kyc_pod_builder(&params, &gov_id_pod, &pay_stub_pod);
+        let kyc_builder = zu_kyc_pod_builder(&params, &gov_id_pod, &pay_stub_pod).unwrap();
 
-        let mut prover = MockProver {};
+        let mut prover = MockProver::default();
         let kyc_pod = kyc_builder.prove(&mut prover).unwrap();
         let pod = kyc_pod.pod.into_any().downcast::<MockMainPod>().unwrap();
 
@@ -500,9 +830,9 @@ pub mod tests {
 
     #[test]
     fn test_mock_main_great_boy() {
-        let great_boy_builder = great_boy_pod_full_flow();
+        let great_boy_builder = great_boy_pod_full_flow().unwrap();
 
-        let mut prover = MockProver {};
+        let mut prover = MockProver::default();
         let great_boy_pod = great_boy_builder.prove(&mut prover).unwrap();
         let pod = great_boy_pod
             .pod
@@ -517,12 +847,662 @@ pub mod tests {
 
     #[test]
     fn test_mock_main_tickets() {
-        let tickets_builder = tickets_pod_full_flow();
-        let mut prover = MockProver {};
+        let tickets_builder = tickets_pod_full_flow().unwrap();
+        let mut prover = MockProver::default();
         let proof_pod = tickets_builder.prove(&mut prover).unwrap();
         let pod = proof_pod.pod.into_any().downcast::<MockMainPod>().unwrap();
 
         println!("{}", pod);
         assert_eq!(pod.verify(), true);
     }
+
+    #[test]
+    fn test_mock_main_tampered() {
+        let tickets_builder = tickets_pod_full_flow().unwrap();
+        let mut prover = MockProver::default();
+        let proof_pod = tickets_builder.prove(&mut prover).unwrap();
+        let pod = proof_pod.pod.into_any().downcast::<MockMainPod>().unwrap();
+        assert_eq!(pod.verify(), true);
+
+        // Change the value of the first private entry, which the following operations use
+        let mut bad_pod = pod.clone();
+        let i = (bad_pod.offset_input_statements()..bad_pod.statements.len())
+            .find(|&i| bad_pod.statements[i].0 == NativeStatement::ValueOf)
+            .unwrap();
+        bad_pod.statements[i].1[1] = StatementArg::Literal(middleware::Value::from(0));
+        assert_eq!(bad_pod.verify(), false);
+    }
+
+    #[test]
+    fn test_mock_main_id() {
+        let mut prover = MockProver::default();
+        let prove_tickets = |prover: &mut MockProver| {
+            tickets_pod_full_flow()
+                .unwrap()
+                .prove(prover)
+                .unwrap()
+                .pod
+                .into_any()
+                .downcast::<MockMainPod>()
+                .unwrap()
+        };
+        let pod_a = prove_tickets(&mut prover);
+        let pod_b = prove_tickets(&mut prover);
+        assert_ne!(pod_a.id(), PodId::default());
+        // Same statements, same id
+        assert_eq!(pod_a.id(), pod_b.id());
+
+        let (gov_id_builder, pay_stub_builder) =
+            zu_kyc_sign_pod_builders(&middleware::Params::default());
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let gov_id_pod = gov_id_builder.sign(&mut signer).unwrap();
+        let pay_stub_pod = pay_stub_builder.sign(&mut signer).unwrap();
+        let kyc_pod =
+            zu_kyc_pod_builder(&middleware::Params::default(), &gov_id_pod, &pay_stub_pod)
+                .unwrap()
+                .prove(&mut prover)
+                .unwrap();
+        // Different statements, different id
+        assert_ne!(pod_a.id(), kyc_pod.id());
+    }
+
+    #[test]
+    fn test_mock_main_tampered_input_main_pod() {
+        let params = middleware::Params::default();
+        let mut prover = MockProver::default();
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.op(false, op!(gt, 9, 3)).unwrap();
+        builder.pub_op(op!(new_entry, ("score", 42))).unwrap();
+        let child = builder.prove(&mut prover).unwrap();
+
+        let score = OperationArg::try_from((&child, "score")).unwrap();
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_main_pod(child);
+        builder.pub_op(op!(gt, score, 10)).unwrap();
+        let parent = builder.prove(&mut prover).unwrap();
+        let parent = parent.pod.into_any().downcast::<MockMainPod>().unwrap();
+        assert_eq!(parent.verify(), true);
+
+        // Tamper with a private statement of the child, which keeps its public statements and id
+        let mut bad_parent = parent.clone();
+        let mut bad_child = bad_parent.input_main_pods[0]
+            .clone()
+            .into_any()
+            .downcast::<MockMainPod>()
+            .unwrap();
+        let i = bad_child.offset_input_statements();
+        // 9 -> 1, so that the Gt no longer holds
+        bad_child.statements[i].1[1] = StatementArg::Literal(middleware::Value::from(1));
+        assert_eq!(bad_child.verify(), false);
+        bad_parent.input_main_pods[0] = bad_child;
+        assert_eq!(bad_parent.verify(), false);
+    }
+
+    #[test]
+    fn test_mock_main_check_operations() {
+        let params = middleware::Params::default();
+        let mut builder = MainPodBuilder::new(&params);
+        let a = builder.op(false, op!(new_entry, ("a", 5))).unwrap();
+        let b = builder.op(false, op!(new_entry, ("b", 3))).unwrap();
+        // 5 < 3 doesn't hold, but `insert` doesn't check it
+        builder.insert((
+            frontend::Statement(NativeStatement::Lt, vec![a.1[0].clone(), b.1[0].clone()]),
+            op!(lt, &a, &b),
+        ));
+
+        let pod = builder.prove(&mut MockProver::default()).unwrap();
+        assert_eq!(pod.verify(), false);
+
+        let mut prover = MockProver {
+            check_operations: true,
+        };
+        let err = builder.prove(&mut prover).unwrap_err();
+        let index = params.max_input_signed_pods * params.max_signed_pod_values
+            + params.max_input_main_pods * params.max_public_statements
+            + 2;
+        assert!(err
+            .to_string()
+            .starts_with(&format!("Statement {} ", index)));
+    }
+
+    #[test]
+    fn test_mock_main_unresolved_op_arg() {
+        let params = middleware::Params::default();
+        let (a, b) = (
+            AnchoredKey(SELF, hash_str("a")),
+            AnchoredKey(SELF, hash_str("b")),
+        );
+        let value_of_a = middleware::Statement::ValueOf(a.clone(), middleware::Value::from(3));
+        let value_of_b = middleware::Statement::ValueOf(b.clone(), middleware::Value::from(5));
+        // The ValueOf for `b` is referenced but never laid out
+        let statements = [value_of_a.clone(), middleware::Statement::Lt(a, b)];
+        let operations = [
+            middleware::Operation::NewEntry,
+            middleware::Operation::LtFromEntries(value_of_a, value_of_b.clone()),
+        ];
+        let inputs = MainPodInputs {
+            signed_pods: &[],
+            main_pods: &[],
+            statements: &statements,
+            operations: &operations,
+            public_statements: &[],
+        };
+        let err = MockMainPod::new(&params, inputs).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<OperationArgError>(),
+            Some(&OperationArgError::StatementNotFound(value_of_b))
+        );
+    }
+
+    #[test]
+    fn test_mock_main_kvs() {
+        let params = middleware::Params::default();
+        let mut builder = MainPodBuilder::new(&params);
+        builder.pub_op(op!(new_entry, ("score", 42))).unwrap();
+        builder.op(false, op!(new_entry, ("secret", 7))).unwrap();
+        let pod = builder.prove(&mut MockProver::default()).unwrap().pod;
+
+        let kvs = pod.kvs();
+        assert_eq!(
+            kvs.get(&AnchoredKey(pod.id(), hash_str("score"))),
+            Some(&middleware::Value::from(42))
+        );
+        assert_eq!(
+            kvs.get(&AnchoredKey(pod.id(), hash_str(KEY_TYPE))),
+            Some(&middleware::Value(hash_str(VALUE_TYPE).0))
+        );
+        // Private entries aren't exposed
+        assert_eq!(kvs.get(&AnchoredKey(pod.id(), hash_str("secret"))), None);
+        assert_eq!(kvs.len(), 2);
+    }
+
+    #[test]
+    fn test_mock_main_too_many_signed_pods() {
+        let params = middleware::Params::builder()
+            .max_input_signed_pods(1)
+            .build();
+        let (gov_id_builder, pay_stub_builder) = zu_kyc_sign_pod_builders(&params);
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let gov_id_pod = gov_id_builder.sign(&mut signer).unwrap();
+        let pay_stub_pod = pay_stub_builder.sign(&mut signer).unwrap();
+        let kyc_builder = zu_kyc_pod_builder(&params, &gov_id_pod, &pay_stub_pod).unwrap();
+
+        let err = kyc_builder
+            .prove(&mut MockProver::default())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("max_input_signed_pods"), "{}", err);
+    }
+
+    #[test]
+    fn test_mock_main_oversized_signed_pod() {
+        let params = middleware::Params::default();
+        let mut builder = frontend::SignedPodBuilder::new(&params);
+        // Together with the type and signer entries this exceeds max_signed_pod_values
+        for i in 0..params.max_signed_pod_values as i64 {
+            builder.insert(format!("k{}", i), i);
+        }
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let pod = builder.sign(&mut signer).unwrap();
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&pod);
+        builder.pub_op(op!(gt, (&pod, "k1"), (&pod, "k0"))).unwrap();
+        let err = builder
+            .prove(&mut MockProver::default())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("max_signed_pod_values"), "{}", err);
+    }
+
+    #[test]
+    fn test_mock_main_prv_statements() {
+        let params = middleware::Params::default();
+        let (gov_id_builder, pay_stub_builder) = zu_kyc_sign_pod_builders(&params);
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let gov_id_pod = gov_id_builder.sign(&mut signer).unwrap();
+        let pay_stub_pod = pay_stub_builder.sign(&mut signer).unwrap();
+        let kyc_builder = zu_kyc_pod_builder(&params, &gov_id_pod, &pay_stub_pod).unwrap();
+        let kyc_pod = kyc_builder.prove(&mut MockProver::default()).unwrap();
+        let pod = kyc_pod.pod.into_any().downcast::<MockMainPod>().unwrap();
+
+        let prv_statements = pod.prv_statements();
+        for st in prv_statements.iter().filter(|st| !st.is_none()) {
+            println!("{}", st);
+        }
+        assert_eq!(prv_statements.len(), params.max_priv_statements());
+        assert_eq!(
+            pod.all_statements().len(),
+            pod.operations().len() + pod.offset_input_statements()
+        );
+        assert_eq!(
+            prv_statements.iter().filter(|st| !st.is_none()).count(),
+            kyc_builder.statements.len()
+        );
+    }
+
+    #[test]
+    fn test_mock_main_layout_compact() {
+        let params = middleware::Params::default();
+        let (gov_id_builder, pay_stub_builder) = zu_kyc_sign_pod_builders(&params);
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let gov_id_pod = gov_id_builder.sign(&mut signer).unwrap();
+        let pay_stub_pod = pay_stub_builder.sign(&mut signer).unwrap();
+        let kyc_builder = zu_kyc_pod_builder(&params, &gov_id_pod, &pay_stub_pod).unwrap();
+        let kyc_pod = kyc_builder.prove(&mut MockProver::default()).unwrap();
+        let pod = kyc_pod.pod.into_any().downcast::<MockMainPod>().unwrap();
+
+        let signed_pods = [&gov_id_pod.pod, &pay_stub_pod.pod];
+        let inputs = MainPodInputs {
+            signed_pods: &signed_pods,
+            main_pods: &[],
+            statements: &pod
+                .input_statements
+                .iter()
+                .filter(|st| !st.is_none())
+                .map(|st| st.clone().try_into().unwrap())
+                .collect::<Vec<_>>(),
+            operations: &[],
+            public_statements: &pod.public_statements()[1..]
+                .iter()
+                .filter(|st| !st.is_none())
+                .map(|st| st.clone().try_into().unwrap())
+                .collect::<Vec<_>>(),
+        };
+        let compact = MockMainPod::layout_statements_compact(&inputs);
+        let padded = MockMainPod::layout_statements(&params, &inputs).unwrap();
+        assert!(compact.len() < padded.len());
+
+        let to_middleware = |sts: &[Statement]| {
+            sts.iter()
+                .filter(|st| !st.is_none())
+                .map(|st| middleware::Statement::try_from(st.clone()).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(to_middleware(&compact), to_middleware(&padded));
+        assert_eq!(padded, pod.statements);
+    }
+
+    #[test]
+    fn test_mock_main_to_fields() -> Result<()> {
+        let (gov_id_builder, pay_stub_builder) =
+            zu_kyc_sign_pod_builders(&middleware::Params::default());
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let gov_id_a = gov_id_builder.sign(&mut signer)?;
+        let gov_id_b = gov_id_builder.sign(&mut signer)?;
+        assert_eq!(gov_id_a.pod.to_fields(), gov_id_b.pod.to_fields());
+
+        let pay_stub = pay_stub_builder.sign(&mut signer)?;
+        assert_ne!(gov_id_a.pod.to_fields(), pay_stub.pod.to_fields());
+
+        let prove_kyc = || -> Result<Box<dyn Pod>> {
+            let kyc_builder =
+                zu_kyc_pod_builder(&middleware::Params::default(), &gov_id_a, &pay_stub)?;
+            Ok(kyc_builder.prove(&mut MockProver::default())?.pod)
+        };
+        let (kyc_a, kyc_b) = (prove_kyc()?, prove_kyc()?);
+        assert_eq!(kyc_a.to_fields(), kyc_b.to_fields());
+        assert_eq!(kyc_a.id(), kyc_b.id());
+
+        // The id hashes the same canonical encoding, so it doesn't depend on the statement order
+        let kyc = kyc_a.into_any().downcast::<MockMainPod>().unwrap();
+        let mut public_statements = kyc.public_statements().to_vec();
+        public_statements.reverse();
+        assert_eq!(hash_statements(&public_statements)?, kyc.id.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_main_operation_resolve() -> Result<()> {
+        let pod = tickets_pod_full_flow()?.prove(&mut MockProver::default())?;
+        let pod = pod.pod.into_any().downcast::<MockMainPod>().unwrap();
+
+        let offset = pod.offset_input_statements();
+        for (i, op) in pod.operations.iter().enumerate() {
+            let statements = &pod.statements[..offset + i];
+            let indices: Vec<_> =
+                op.1.iter()
+                    .filter_map(|arg| match arg {
+                        super::OperationArg::Index(i) => Some(*i),
+                        super::OperationArg::None | super::OperationArg::MerkleProof(_) => None,
+                    })
+                    .collect();
+            let rebuilt = Operation::from_indices(op.0 as usize, &indices)?;
+            let resolved = rebuilt.resolve(statements)?;
+            assert_eq!(resolved, op.resolve(statements)?);
+            let st = middleware::Statement::try_from(pod.statements[offset + i].clone())?;
+            assert!(resolved.check(&st)?);
+        }
+
+        let op = Operation::from_indices(NativeOperation::CopyStatement as usize, &[1000])?;
+        assert!(op.resolve(&pod.statements).is_err());
+        assert!(Operation::from_indices(1000, &[]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_main_to_graphviz() -> Result<()> {
+        let pod = tickets_pod_full_flow()?.prove(&mut MockProver::default())?;
+        let pod = pod.pod.into_any().downcast::<MockMainPod>().unwrap();
+
+        let dot = pod.to_graphviz().unwrap();
+        assert!(dot.starts_with("digraph "));
+        assert!(dot.ends_with("}\n"));
+        let nodes = dot
+            .lines()
+            .filter(|l| l.contains("[label=") && !l.contains("->"));
+        assert_eq!(
+            nodes.count(),
+            pod.statements.iter().filter(|st| !st.is_none()).count()
+        );
+        let edges = dot.lines().filter(|l| l.contains("->"));
+        let op_args = pod
+            .operations
+            .iter()
+            .flat_map(|op| op.1.iter())
+            .filter(|arg| !arg.is_none());
+        assert_eq!(edges.count(), op_args.count());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_main_padding() -> Result<()> {
+        let params = middleware::Params::default();
+        let pod = tickets_pod_full_flow()?.prove(&mut MockProver::default())?;
+        let pod = pod.pod.into_any().downcast::<MockMainPod>().unwrap();
+
+        let statement_none = MockMainPod::statement_none(&params);
+        assert_eq!(statement_none.1.len(), params.max_statement_args);
+        assert_eq!(
+            pod.statements.len(),
+            pod.offset_public_statements() + params.max_public_statements
+        );
+        for st in pod.statements.iter() {
+            assert_eq!(st.1.len(), params.max_statement_args);
+            if st.is_none() {
+                assert_eq!(st, &statement_none);
+            }
+        }
+
+        let operation_none = MockMainPod::operation_none(&params);
+        assert_eq!(operation_none.1.len(), params.max_operation_args);
+        for op in pod.operations.iter() {
+            if op.0 == NativeOperation::None {
+                assert_eq!(op, &operation_none);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_main_find_op_arg() -> Result<()> {
+        let params = middleware::Params::builder().max_statements(60).build();
+        let mut builder = MainPodBuilder::new(&params);
+        let mut prev = builder.op(false, op!(new_entry, ("v0", 0)))?;
+        for i in 1..25 {
+            let st = builder.op(
+                false,
+                op!(new_entry, (format!("v{}", i).as_str(), i as i64)),
+            )?;
+            prev = builder.op(false, op!(gt, st, prev))?;
+        }
+        builder.reveal(&prev)?;
+        let pod = builder.prove(&mut MockProver::default())?;
+        let pod = pod.pod.into_any().downcast::<MockMainPod>().unwrap();
+        assert!(pod.verify());
+
+        // The indexed lookup resolves to the same index as a scan for the first match
+        let index = MockMainPod::index_statements(&pod.statements);
+        for st in pod.statements.iter().filter(|st| !st.is_none()) {
+            let st = middleware::Statement::try_from(st.clone())?;
+            let scanned = pod
+                .statements
+                .iter()
+                .position(|s| middleware::Statement::try_from(s.clone()).ok().as_ref() == Some(&st))
+                .unwrap();
+            assert_eq!(
+                MockMainPod::find_op_arg(&index, &st)?,
+                super::OperationArg::Index(scanned)
+            );
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_mock_main_verify_tracing() {
+        use std::fmt::Write;
+        use std::sync::{Arc, Mutex};
+        use tracing::{field, span, Event, Metadata, Subscriber};
+
+        struct FieldsVisitor(String);
+        impl field::Visit for FieldsVisitor {
+            fn record_debug(&mut self, field: &field::Field, value: &dyn fmt::Debug) {
+                write!(self.0, "{}={:?} ", field.name(), value).unwrap();
+            }
+        }
+
+        /// Subscriber that keeps the fields of every event
+        struct EventCollector(Arc<Mutex<Vec<String>>>);
+        impl Subscriber for EventCollector {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+                span::Id::from_u64(1)
+            }
+            fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+            fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+            fn event(&self, event: &Event<'_>) {
+                let mut visitor = FieldsVisitor(String::new());
+                event.record(&mut visitor);
+                self.0.lock().unwrap().push(visitor.0);
+            }
+            fn enter(&self, _: &span::Id) {}
+            fn exit(&self, _: &span::Id) {}
+        }
+
+        let pod = tickets_pod_full_flow()
+            .unwrap()
+            .prove(&mut MockProver::default())
+            .unwrap();
+        let mut pod = pod.pod.into_any().downcast::<MockMainPod>().unwrap();
+        let i = (pod.offset_input_statements()..pod.statements.len())
+            .find(|&i| pod.statements[i].0 == NativeStatement::ValueOf)
+            .unwrap();
+        pod.statements[i].1[1] = StatementArg::Literal(middleware::Value::from(0));
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let collector = EventCollector(events.clone());
+        let valid = tracing::subscriber::with_default(collector, || pod.verify());
+        assert!(!valid);
+        let events = events.lock().unwrap();
+        assert!(events
+            .iter()
+            .any(|e| e.contains("statement isn't justified by its operation")));
+    }
+
+    #[test]
+    fn test_mock_main_pub_operations() -> Result<()> {
+        let builder = tickets_pod_full_flow()?;
+        let pod = builder.prove(&mut MockProver::default())?;
+        let statements = pod.pod.pub_statements();
+        let operations = pod.pod.pub_operations();
+        assert_eq!(statements.len(), operations.len());
+
+        // The first public statement is the pod type entry, followed by the builder's public
+        // statements, each paired with the operation used to derive it.
+        assert_eq!(operations[0], middleware::Operation::NewEntry);
+        let builder_ops = builder
+            .public_statements
+            .iter()
+            .map(|st| {
+                let i = builder.statements.iter().position(|s| s == st).unwrap();
+                builder.operations[i].0
+            })
+            .collect::<Vec<_>>();
+        let pub_ops = operations[1..=builder_ops.len()]
+            .iter()
+            .map(|op| op.code())
+            .collect::<Vec<_>>();
+        assert_eq!(pub_ops, builder_ops);
+        for (st, op) in statements.iter().zip(operations.iter()).skip(1) {
+            assert!(op.check(st)?, "{} <- {:?}", st, op);
+        }
+
+        // A public statement copied from another public statement resolves to the operation of
+        // the original statement
+        let pod = pod.pod.into_any().downcast::<MockMainPod>().unwrap();
+        let offset_public_statements = pod.offset_public_statements();
+        let last = pod.statements.len() - 1;
+        let mut copy_pod = pod.clone();
+        copy_pod.statements[last] = copy_pod.statements[offset_public_statements + 1].clone();
+        copy_pod.operations[last - pod.offset_input_statements()] = Operation(
+            NativeOperation::CopyStatement,
+            vec![super::OperationArg::Index(offset_public_statements + 1)],
+        );
+        let copy_operations = copy_pod.pub_operations();
+        assert_eq!(copy_operations.last(), Some(&operations[1]));
+
+        // Operations that can't be resolved give no operations instead of panicking
+        let mut bad_pod = pod.clone();
+        bad_pod.operations[last - pod.offset_input_statements()] = Operation(
+            NativeOperation::CopyStatement,
+            vec![super::OperationArg::Index(pod.statements.len())],
+        );
+        assert!(bad_pod.pub_operations().is_empty());
+
+        // Pods that don't keep their operations expose none
+        assert!(NonePod {}.pub_operations().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_main_verify_detailed() -> Result<()> {
+        let pod = tickets_pod_full_flow()?.prove(&mut MockProver::default())?;
+        let pod = pod.pod.into_any().downcast::<MockMainPod>().unwrap();
+        assert_eq!(pod.verify_detailed(), Ok(()));
+
+        // Point the first derived (non ValueOf) private statement to another key
+        let mut bad_pod = pod.clone();
+        let index = (bad_pod.offset_input_statements()..bad_pod.offset_public_statements())
+            .find(|&i| {
+                !matches!(
+                    bad_pod.statements[i].0,
+                    NativeStatement::None | NativeStatement::ValueOf
+                )
+            })
+            .unwrap();
+        bad_pod.statements[index].1[0] = StatementArg::Key(AnchoredKey(SELF, hash_str("tampered")));
+        let err = bad_pod.verify_detailed().unwrap_err();
+        assert!(
+            matches!(err, VerifyError::StatementNotJustified { index: i, .. } if i == index),
+            "{}",
+            err
+        );
+        assert!(!bad_pod.verify());
+
+        let mut bad_pod = pod.clone();
+        bad_pod.id = PodId(hash_str("other"));
+        assert_eq!(pod.id_from_content(), pod.id());
+        assert_ne!(bad_pod.id_from_content(), bad_pod.id());
+        assert_eq!(bad_pod.verify_detailed(), Err(VerifyError::IdMismatch));
+        assert!(!bad_pod.verify());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_main_copies_check() -> Result<()> {
+        let params = middleware::Params::default();
+        let mut prover = MockProver::default();
+
+        let mut builder = MainPodBuilder::new(&params);
+        let a = builder.op(false, op!(new_entry, ("a", 9)))?;
+        let b = builder.op(false, op!(new_entry, ("b", 3)))?;
+        builder.pub_op(op!(gt, &a, &b))?;
+        let child = builder.prove(&mut prover)?;
+        let child_id = child.id();
+
+        let mut builder = MainPodBuilder::new(&params);
+        let gt = builder
+            .add_main_pod(child)
+            .into_iter()
+            .find(|st| st.0 == NativeStatement::Gt)
+            .unwrap();
+        builder.pub_op(op!(copy, &gt))?;
+        let pod = builder.prove(&mut prover)?;
+        let pod = pod.pod.into_any().downcast::<MockMainPod>().unwrap();
+        assert_eq!(pod.verify_detailed(), Ok(()));
+
+        // Replace the copied statement, including its slot in the input pod region, by a
+        // fabricated one
+        let (ak_a, ak_b) = (
+            AnchoredKey(child_id, hash_str("a")),
+            AnchoredKey(child_id, hash_str("b")),
+        );
+        let mut genuine = Statement::from(middleware::Statement::Gt(ak_a.clone(), ak_b.clone()));
+        MockMainPod::pad_statement(&params, &mut genuine)?;
+        let mut fabricated = Statement::from(middleware::Statement::Lt(ak_a, ak_b));
+        MockMainPod::pad_statement(&params, &mut fabricated)?;
+        let mut bad_pod = pod.clone();
+        for st in bad_pod.statements.iter_mut().filter(|st| **st == genuine) {
+            *st = fabricated.clone();
+        }
+        let index = (bad_pod.offset_input_statements()..)
+            .find(|i| bad_pod.statements[*i] == fabricated)
+            .unwrap();
+        assert!(matches!(
+            bad_pod.verify_detailed(),
+            Err(VerifyError::CopyNotInInput { index: i, .. }) if i == index
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_main_max_statement_args() -> Result<()> {
+        let params = middleware::Params {
+            max_statement_args: 2,
+            ..Default::default()
+        };
+        let mut builder = MainPodBuilder::new(&params);
+        let a = builder.op(false, op!(new_entry, ("a", 5)))?;
+        let b = builder.op(false, op!(new_entry, ("b", 2)))?;
+        let c = builder.op(false, op!(new_entry, ("c", 3)))?;
+        builder.pub_op(op!(sum, &a, &b, &c))?;
+        let err = builder.prove(&mut MockProver::default()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<crate::error::Pod2Error>(),
+            Some(crate::error::Pod2Error::InvalidParams(_))
+        ));
+
+        // Padding a statement with too many arguments errors instead of panicking
+        let mut sum = Statement::from(middleware::Statement::SumOf(
+            AnchoredKey(SELF, hash_str("a")),
+            AnchoredKey(SELF, hash_str("b")),
+            AnchoredKey(SELF, hash_str("c")),
+        ));
+        assert!(MockMainPod::pad_statement(&params, &mut sum).is_err());
+
+        Ok(())
+    }
 }
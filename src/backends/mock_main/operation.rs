@@ -1,8 +1,9 @@
 use std::fmt;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
 use crate::middleware::{self, NativeOperation};
+use crate::primitives::merkletree::MerkleProof;
 
 use super::Statement;
 
@@ -10,6 +11,7 @@ use super::Statement;
 pub enum OperationArg {
     None,
     Index(usize),
+    MerkleProof(MerkleProof),
 }
 
 impl OperationArg {
@@ -21,14 +23,20 @@ impl OperationArg {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum OperationArgError {
     KeyNotFound,
-    StatementNotFound,
+    /// The referenced statement isn't among the laid out statements.
+    StatementNotFound(middleware::Statement),
+    /// The index is past the statements available to the operation.
+    IndexOutOfRange(usize),
 }
 
 impl std::fmt::Display for OperationArgError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             OperationArgError::KeyNotFound => write!(f, "Key not found"),
-            OperationArgError::StatementNotFound => write!(f, "Statement not found"),
+            OperationArgError::StatementNotFound(st) => write!(f, "Statement not found: {}", st),
+            OperationArgError::IndexOutOfRange(i) => {
+                write!(f, "Statement index {} out of range", i)
+            }
         }
     }
 }
@@ -39,16 +47,39 @@ impl std::error::Error for OperationArgError {}
 pub struct Operation(pub NativeOperation, pub Vec<OperationArg>);
 
 impl Operation {
-    pub fn deref(&self, statements: &[Statement]) -> Result<crate::middleware::Operation> {
-        let deref_args = self
+    /// Build an operation from its op code and the indices of its argument statements.
+    pub fn from_indices(op_code: usize, indices: &[usize]) -> Result<Self> {
+        let op_type = NativeOperation::from_repr(op_code)
+            .ok_or_else(|| anyhow!("Invalid operation code {}.", op_code))?;
+        Ok(Self(
+            op_type,
+            indices.iter().map(|i| OperationArg::Index(*i)).collect(),
+        ))
+    }
+
+    /// Resolve the argument indices against `statements` into a typed middleware operation.
+    pub fn resolve(&self, statements: &[Statement]) -> Result<middleware::Operation> {
+        let args = self
             .1
             .iter()
             .flat_map(|arg| match arg {
-                OperationArg::None => None,
-                OperationArg::Index(i) => Some(statements[*i].clone().try_into()),
+                OperationArg::None | OperationArg::MerkleProof(_) => None,
+                OperationArg::Index(i) => Some(
+                    statements
+                        .get(*i)
+                        .ok_or(OperationArgError::IndexOutOfRange(*i).into())
+                        .and_then(|st| st.clone().try_into()),
+                ),
             })
-            .collect::<Result<Vec<crate::middleware::Statement>>>()?;
-        middleware::Operation::op(self.0, &deref_args)
+            .collect::<Result<Vec<middleware::Statement>>>()?;
+        let proof = self.1.iter().find_map(|arg| match arg {
+            OperationArg::MerkleProof(proof) => Some(proof.clone()),
+            _ => None,
+        });
+        Ok(match proof {
+            Some(proof) => middleware::Operation::op_with_proof(self.0, &args, proof)?,
+            None => middleware::Operation::op(self.0, &args)?,
+        })
     }
 }
 
@@ -63,6 +94,7 @@ impl fmt::Display for Operation {
                 match arg {
                     OperationArg::None => write!(f, "none")?,
                     OperationArg::Index(i) => write!(f, "{:02}", i)?,
+                    OperationArg::MerkleProof(_) => write!(f, "proof")?,
                 }
             }
         }
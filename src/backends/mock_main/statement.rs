@@ -61,12 +61,46 @@ impl TryFrom<Statement> for middleware::Statement {
             }
             (NS::Gt, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), None), 2) => S::Gt(ak1, ak2),
             (NS::Lt, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), None), 2) => S::Lt(ak1, ak2),
+            (NS::Gteq, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), None), 2) => S::Gteq(ak1, ak2),
+            (NS::Lteq, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), None), 2) => S::Lteq(ak1, ak2),
             (NS::Contains, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), None), 2) => {
                 S::Contains(ak1, ak2)
             }
             (NS::NotContains, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), None), 2) => {
                 S::NotContains(ak1, ak2)
             }
+            (NS::EqualLiteral, (Some(SA::Key(ak)), Some(SA::Literal(v)), None), 2) => {
+                S::EqualLiteral(ak, v)
+            }
+            (NS::GtLiteral, (Some(SA::Key(ak)), Some(SA::Literal(v)), None), 2) => {
+                S::GtLiteral(ak, v)
+            }
+            (NS::LtLiteral, (Some(SA::Key(ak)), Some(SA::Literal(v)), None), 2) => {
+                S::LtLiteral(ak, v)
+            }
+            (NS::DivOf, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), Some(SA::Key(ak3))), 3) => {
+                S::DivOf(ak1, ak2, ak3)
+            }
+            (NS::ModOf, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), Some(SA::Key(ak3))), 3) => {
+                S::ModOf(ak1, ak2, ak3)
+            }
+            (NS::AndOf, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), Some(SA::Key(ak3))), 3) => {
+                S::AndOf(ak1, ak2, ak3)
+            }
+            (NS::OrOf, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), Some(SA::Key(ak3))), 3) => {
+                S::OrOf(ak1, ak2, ak3)
+            }
+            (NS::NotOf, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), None), 2) => S::NotOf(ak1, ak2),
+            (
+                NS::InRange,
+                (Some(SA::Key(ak)), Some(SA::Literal(min)), Some(SA::Literal(max))),
+                3,
+            ) => S::InRange(ak, min, max),
+            (
+                NS::EqualsContainedValue,
+                (Some(SA::Key(ak1)), Some(SA::Key(ak2)), Some(SA::Literal(key))),
+                3,
+            ) => S::EqualsContainedValue(ak1, ak2, key),
             (NS::SumOf, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), Some(SA::Key(ak3))), 3) => {
                 S::SumOf(ak1, ak2, ak3)
             }
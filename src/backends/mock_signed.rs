@@ -1,39 +1,165 @@
+use crate::backends::SignedPodBackend;
 use crate::middleware::{
-    containers::Dictionary, hash_str, AnchoredKey, Hash, Params, Pod, PodId, PodSigner, PodType,
-    Statement, Value, KEY_SIGNER, KEY_TYPE,
+    containers::{Array, Dictionary},
+    hash_str, AnchoredKey, Hash, Params, Pod, PodId, PodSigner, PodType, Statement, Value, F,
+    KEY_SIGNER, KEY_SIGNERS, KEY_TYPE, NULL,
 };
 use crate::primitives::merkletree::MerkleTree;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use plonky2::field::types::{Field, PrimeField64};
+use plonky2::hash::poseidon::PoseidonHash;
+use plonky2::plonk::config::Hasher;
+use serde::{Deserialize, Serialize};
 use std::any::Any;
+use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher as _};
 
 pub struct MockSigner {
     pub pk: String,
 }
 
+impl MockSigner {
+    /// Deterministic signer whose public key is derived from `seed` via Poseidon.
+    pub fn from_seed(seed: u64) -> Self {
+        let digest = PoseidonHash::hash_no_pad(&[F::from_noncanonical_u64(seed)]).elements;
+        Self {
+            pk: digest
+                .iter()
+                .map(|f| format!("{:016x}", f.to_canonical_u64()))
+                .collect(),
+        }
+    }
+
+    /// Signer with a non-deterministic public key.
+    pub fn random() -> Self {
+        Self::from_seed(RandomState::new().build_hasher().finish())
+    }
+
+    /// Value stored at `KEY_SIGNER` in the pods signed by this signer.
+    pub fn pk_value(&self) -> Value {
+        Value(hash_str(&self.pk).0)
+    }
+}
+
 impl PodSigner for MockSigner {
     fn sign(&mut self, _params: &Params, kvs: &HashMap<Hash, Value>) -> Result<Box<dyn Pod>> {
         let mut kvs = kvs.clone();
-        let pk_hash = hash_str(&self.pk);
-        kvs.insert(hash_str(&KEY_SIGNER), Value(pk_hash.0));
+        let pk_value = self.pk_value();
+        kvs.insert(hash_str(&KEY_SIGNER), pk_value);
         kvs.insert(hash_str(&KEY_TYPE), Value::from(PodType::MockSigned));
 
         let dict = Dictionary::new(&kvs);
         let id = PodId(dict.commitment());
-        let signature = format!("{}_signed_by_{}", id, pk_hash);
+        let signature = mock_signature(&pk_value, &id);
         Ok(Box::new(MockSignedPod {
             dict,
             id,
             signature,
+            cosignatures: Vec::new(),
+        }))
+    }
+
+    fn pubkey(&self) -> Option<Value> {
+        Some(self.pk_value())
+    }
+}
+
+/// Signs a pod with several signers.  The public keys are committed as an `Array` at
+/// `KEY_SIGNERS` instead of `KEY_SIGNER`, so the pod id commits to the full set of signers, and
+/// each signer signs the id.  Every signer must have a public key.
+pub struct MockMultiSigner {
+    pub signers: Vec<Box<dyn PodSigner>>,
+}
+
+impl PodSigner for MockMultiSigner {
+    fn sign(&mut self, _params: &Params, kvs: &HashMap<Hash, Value>) -> Result<Box<dyn Pod>> {
+        if self.signers.is_empty() {
+            return Err(anyhow!("A pod needs at least one signer."));
+        }
+        let pk_values = self
+            .signers
+            .iter()
+            .map(|signer| {
+                signer.pubkey().ok_or(anyhow!(
+                    "A signer without a public key can't co-sign a pod."
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut kvs = kvs.clone();
+        kvs.insert(
+            hash_str(&KEY_SIGNERS),
+            Value(Array::new(&pk_values).commitment().0),
+        );
+        kvs.insert(hash_str(&KEY_TYPE), Value::from(PodType::MockSigned));
+
+        let dict = Dictionary::new(&kvs);
+        let id = PodId(dict.commitment());
+        let cosignatures = pk_values
+            .into_iter()
+            .map(|pk_value| (pk_value, mock_signature(&pk_value, &id)))
+            .collect();
+        Ok(Box::new(MockSignedPod {
+            dict,
+            id,
+            signature: NULL,
+            cosignatures,
         }))
     }
 }
 
-#[derive(Clone, Debug)]
+/// Mock signature over the pod id: Poseidon(pk_hash ‖ id).  It only depends on public values,
+/// so anyone can forge it; it just makes the signature commit to the signer and the id.
+fn mock_signature(pk_hash: &Value, id: &PodId) -> Hash {
+    Hash(PoseidonHash::hash_no_pad(&[pk_hash.0, id.0 .0].concat()).elements)
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(into = "MockSignedPodData", from = "MockSignedPodData")]
 pub struct MockSignedPod {
     id: PodId,
-    signature: String,
+    /// Signature by the key at `KEY_SIGNER`, `NULL` for pods with several signers
+    signature: Hash,
     dict: Dictionary,
+    /// Public keys committed at `KEY_SIGNERS` with their signatures, for pods with several
+    /// signers
+    cosignatures: Vec<(Value, Hash)>,
+}
+
+/// Serialized form of a [MockSignedPod], with the key-values sorted by key.  Deserializing
+/// doesn't verify the pod.
+#[derive(Serialize, Deserialize)]
+struct MockSignedPodData {
+    id: PodId,
+    signature: Hash,
+    kvs: Vec<(Hash, Value)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    cosignatures: Vec<(Value, Hash)>,
+}
+
+impl From<MockSignedPod> for MockSignedPodData {
+    fn from(pod: MockSignedPod) -> Self {
+        let mut kvs: Vec<_> = pod.dict.iter().map(|(k, v)| (Hash(k.0), *v)).collect();
+        kvs.sort();
+        Self {
+            id: pod.id,
+            signature: pod.signature,
+            kvs,
+            cosignatures: pod.cosignatures,
+        }
+    }
+}
+
+impl From<MockSignedPodData> for MockSignedPod {
+    fn from(data: MockSignedPodData) -> Self {
+        Self {
+            id: data.id,
+            signature: data.signature,
+            dict: Dictionary::new(&data.kvs.into_iter().collect()),
+            cosignatures: data.cosignatures,
+        }
+    }
 }
 
 impl Pod for MockSignedPod {
@@ -48,43 +174,66 @@ impl Pod for MockSignedPod {
         }
 
         // Verify id
-        let mt = MerkleTree::new(
-            &self
-                .dict
-                .iter()
-                .map(|(&k, &v)| (k, v))
-                .collect::<HashMap<Value, Value>>(),
-        );
-        let id = PodId(mt.root());
+        let id = self.id_from_content();
         if id != self.id {
             return false;
         }
 
-        // Verify signature
-        let pk_hash = match self.dict.get(&hash_str(&KEY_SIGNER).into()) {
-            Ok(v) => v,
-            Err(_) => return false,
-        };
-        let signature = format!("{}_signed_by_{}", id, pk_hash);
-        if signature != self.signature {
-            return false;
+        // Verify signatures, either by the single signer or by all the signers
+        let signer = self.dict.get(&hash_str(&KEY_SIGNER).into());
+        let signers = self.dict.get(&hash_str(&KEY_SIGNERS).into());
+        match (signer, signers) {
+            (Ok(pk_hash), Err(_)) => {
+                mock_signature(&pk_hash, &id) == self.signature && self.cosignatures.is_empty()
+            }
+            (Err(_), Ok(signers)) => {
+                let pk_values = self
+                    .cosignatures
+                    .iter()
+                    .map(|(pk_hash, _)| *pk_hash)
+                    .collect::<Vec<_>>();
+                !pk_values.is_empty()
+                    && self.signature == NULL
+                    && Value(Array::new(&pk_values).commitment().0) == signers
+                    && self
+                        .cosignatures
+                        .iter()
+                        .all(|(pk_hash, signature)| mock_signature(pk_hash, &id) == *signature)
+            }
+            _ => false,
         }
-
-        return true;
     }
 
     fn id(&self) -> PodId {
         self.id
     }
 
+    /// Root of the merkle tree of the key-values
+    fn id_from_content(&self) -> PodId {
+        let mt = MerkleTree::new(
+            &self
+                .dict
+                .iter()
+                .map(|(&k, &v)| (k, v))
+                .collect::<HashMap<Value, Value>>(),
+        );
+        PodId(mt.root())
+    }
+
+    /// Sorted by key, so that the order doesn't depend on the `HashMap` iteration order.
     fn pub_statements(&self) -> Vec<Statement> {
         let id = self.id();
         self.dict
             .iter()
+            .sorted_by_key(|(k, _)| *k)
             .map(|(k, v)| Statement::ValueOf(AnchoredKey(id, Hash(k.0)), *v))
             .collect()
     }
 
+    fn to_json(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(SignedPodBackend::MockSigned(self.clone())).ok()
+    }
+
     fn into_any(self: Box<Self>) -> Box<dyn Any> {
         self
     }
@@ -94,8 +243,7 @@ impl Pod for MockSignedPod {
 pub mod tests {
     use super::*;
     use crate::frontend;
-    use crate::middleware::{self, F, NULL};
-    use plonky2::field::types::Field;
+    use crate::middleware;
     use std::iter;
 
     #[test]
@@ -115,7 +263,7 @@ pub mod tests {
         println!("kvs: {:?}", pod.kvs());
 
         let mut bad_pod = pod.clone();
-        bad_pod.signature = "".into();
+        bad_pod.signature = NULL;
         assert_eq!(bad_pod.verify(), false);
 
         let mut bad_pod = pod.clone();
@@ -146,4 +294,188 @@ pub mod tests {
         bad_pod.dict.mt = bad_mt;
         assert_eq!(bad_pod.verify(), false);
     }
+
+    #[test]
+    fn test_mock_signed_tampered_kv() {
+        let params = middleware::Params::default();
+        let mut pod = frontend::SignedPodBuilder::new(&params);
+        pod.insert("idNumber", "4242424242");
+        pod.insert("dateOfBirth", 1169909384);
+
+        let mut signer = MockSigner { pk: "Molly".into() };
+        let pod = pod.sign(&mut signer).unwrap();
+        let pod = pod.pod.into_any().downcast::<MockSignedPod>().unwrap();
+        assert_eq!(pod.verify(), true);
+
+        // Change a value after signing, keeping the old id and signature
+        let mut bad_pod = pod.clone();
+        let bad_kvs = bad_pod
+            .kvs()
+            .into_iter()
+            .map(|(AnchoredKey(_, k), v)| {
+                if k == hash_str("dateOfBirth") {
+                    (Value(k.0), Value::from(0))
+                } else {
+                    (Value(k.0), v)
+                }
+            })
+            .collect::<HashMap<Value, Value>>();
+        bad_pod.dict.mt = MerkleTree::new(&bad_kvs);
+        assert_eq!(bad_pod.verify(), false);
+
+        // Re-deriving the id alone fails because the stale signature doesn't match the new id
+        bad_pod.id = PodId(bad_pod.dict.mt.root());
+        assert_eq!(bad_pod.verify(), false);
+
+        // The mock signature only depends on the public `KEY_SIGNER` value and the id, so it's
+        // forgeable by design: re-signing the new id makes the tampered pod verify
+        bad_pod.signature = mock_signature(&signer.pk_value(), &bad_pod.id);
+        assert_eq!(bad_pod.verify(), true);
+    }
+
+    #[test]
+    fn test_mock_signed_tampered_signer() {
+        let params = middleware::Params::default();
+        let mut pod = frontend::SignedPodBuilder::new(&params);
+        pod.insert("idNumber", "4242424242");
+
+        let mut signer = MockSigner { pk: "Molly".into() };
+        let pod = pod.sign(&mut signer).unwrap();
+        let pod = pod.pod.into_any().downcast::<MockSignedPod>().unwrap();
+
+        // Claim another signer, with a consistent id but the original signature
+        let mut bad_pod = pod.clone();
+        let bad_kvs = bad_pod
+            .kvs()
+            .into_iter()
+            .map(|(AnchoredKey(_, k), v)| {
+                if k == hash_str(KEY_SIGNER) {
+                    (Value(k.0), Value(hash_str("Mallory").0))
+                } else {
+                    (Value(k.0), v)
+                }
+            })
+            .collect::<HashMap<Value, Value>>();
+        bad_pod.dict.mt = MerkleTree::new(&bad_kvs);
+        bad_pod.id = PodId(bad_pod.dict.mt.root());
+        assert_eq!(bad_pod.verify(), false);
+    }
+
+    #[test]
+    fn test_mock_signed_id_from_content() {
+        let params = middleware::Params::default();
+        let mut pod = frontend::SignedPodBuilder::new(&params);
+        pod.insert("idNumber", "4242424242");
+
+        let mut signer = MockSigner { pk: "Molly".into() };
+        let pod = pod.sign(&mut signer).unwrap();
+        let pod = pod.pod.into_any().downcast::<MockSignedPod>().unwrap();
+        assert_eq!(pod.id_from_content(), pod.id());
+
+        // An id taken from another pod, signed to look legitimate
+        let mut bad_pod = pod.clone();
+        bad_pod.id = PodId(hash_str("other"));
+        bad_pod.signature = mock_signature(&signer.pk_value(), &bad_pod.id);
+        assert_ne!(bad_pod.id_from_content(), bad_pod.id());
+        assert_eq!(bad_pod.verify(), false);
+    }
+
+    #[test]
+    fn test_mock_multi_signer() {
+        let params = middleware::Params::default();
+        let mut builder = frontend::SignedPodBuilder::new(&params);
+        builder.insert("idNumber", "4242424242");
+        let signers = |pks: &[&str]| MockMultiSigner {
+            signers: pks
+                .iter()
+                .map(|pk| Box::new(MockSigner { pk: pk.to_string() }) as Box<dyn PodSigner>)
+                .collect(),
+        };
+
+        let pod = builder.sign(&mut signers(&["Molly", "Bob"])).unwrap();
+        assert!(pod.verify());
+        assert_eq!(pod.signer(), None);
+        let signers_value = Value(
+            Array::new(&vec![
+                MockSigner { pk: "Molly".into() }.pk_value(),
+                MockSigner { pk: "Bob".into() }.pk_value(),
+            ])
+            .commitment()
+            .0,
+        );
+        assert_eq!(
+            pod.pod
+                .kvs()
+                .get(&AnchoredKey(pod.id(), hash_str(KEY_SIGNERS))),
+            Some(&signers_value)
+        );
+        // The id commits to the full set of signers
+        let other = builder.sign(&mut signers(&["Molly", "Mallory"])).unwrap();
+        assert_ne!(pod.id(), other.id());
+        assert!(builder.sign(&mut signers(&[])).is_err());
+        // A signer without a public key can't be one of the signers
+        let mut nested = signers(&["Molly"]);
+        nested.signers.push(Box::new(signers(&["Bob"])));
+        assert!(builder.sign(&mut nested).is_err());
+
+        let pod = pod.pod.into_any().downcast::<MockSignedPod>().unwrap();
+        assert_eq!(pod.cosignatures.len(), 2);
+
+        // One of the signatures is missing
+        let mut bad_pod = pod.clone();
+        bad_pod.cosignatures.pop();
+        assert_eq!(bad_pod.verify(), false);
+
+        // One of the signatures is tampered
+        let mut bad_pod = pod.clone();
+        bad_pod.cosignatures[1].1 = NULL;
+        assert_eq!(bad_pod.verify(), false);
+
+        // One of the signers is replaced, with a valid signature of the new signer
+        let mut bad_pod = pod.clone();
+        let mallory = MockSigner {
+            pk: "Mallory".into(),
+        }
+        .pk_value();
+        bad_pod.cosignatures[1] = (mallory, mock_signature(&mallory, &bad_pod.id));
+        assert_eq!(bad_pod.verify(), false);
+
+        // A single signature doesn't stand for all the signers
+        let mut bad_pod = pod.clone();
+        bad_pod.signature = pod.cosignatures[0].1;
+        bad_pod.cosignatures.clear();
+        assert_eq!(bad_pod.verify(), false);
+
+        // The single signer signature must be unset
+        let mut bad_pod = pod.clone();
+        bad_pod.signature = pod.cosignatures[0].1;
+        assert_eq!(bad_pod.verify(), false);
+    }
+
+    #[test]
+    fn test_mock_signer_from_seed() {
+        let params = middleware::Params::default();
+        let mut builder = frontend::SignedPodBuilder::new(&params);
+        builder.insert("idNumber", "4242424242");
+
+        let mut signer_a = MockSigner::from_seed(42);
+        let mut signer_b = MockSigner::from_seed(42);
+        assert_eq!(signer_a.pk, signer_b.pk);
+        assert_ne!(signer_a.pk, MockSigner::from_seed(43).pk);
+
+        let pod_a = builder.sign(&mut signer_a).unwrap();
+        let pod_b = builder.sign(&mut signer_b).unwrap();
+        assert_eq!(pod_a.id(), pod_b.id());
+        assert_eq!(
+            pod_a
+                .pod
+                .kvs()
+                .get(&AnchoredKey(pod_a.id(), hash_str(KEY_SIGNER))),
+            Some(&signer_a.pk_value())
+        );
+        assert!(pod_a.verify());
+
+        let mut signer_c = MockSigner::random();
+        assert!(builder.sign(&mut signer_c).unwrap().verify());
+    }
 }
@@ -1,3 +1,21 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::middleware::Pod;
+
 pub mod mock_main;
 pub mod mock_signed;
 pub mod plonky2;
+
+/// Signed pod of one of the backends, tagged by backend in its serialized form.
+#[derive(Serialize, Deserialize)]
+pub(crate) enum SignedPodBackend {
+    MockSigned(mock_signed::MockSignedPod),
+}
+
+/// Load a signed pod serialized with `Pod::to_json`.  The pod isn't verified.
+pub fn signed_pod_from_json(value: serde_json::Value) -> Result<Box<dyn Pod>> {
+    Ok(match serde_json::from_value(value)? {
+        SignedPodBackend::MockSigned(pod) => Box::new(pod),
+    })
+}
@@ -0,0 +1,96 @@
+//! Error type returned by the primitives and the middleware, so that callers can match on
+//! specific failures.  The frontend and the backends keep using `anyhow`, which wraps it.
+
+use thiserror::Error;
+
+use crate::middleware::{OperationError, PodId, Value};
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum Pod2Error {
+    #[error("key {0:?} not found")]
+    KeyNotFound(Value),
+    #[error("key {0:?} already exists with a different value")]
+    KeyAlreadyExists(Value),
+    #[error("invalid merkle proof: {0}")]
+    ProofInvalid(String),
+    #[error("value {0:?} is not an element of the i64 embedding")]
+    NotAnInteger(Value),
+    #[error("statement argument is not {expected}: {arg}")]
+    InvalidStatementArg { expected: &'static str, arg: String },
+    #[error("invalid params: {0}")]
+    InvalidParams(String),
+    #[error("merkle tree leaf of length {0}, expected 4")]
+    InvalidLeafLength(usize),
+    #[error(transparent)]
+    Operation(#[from] OperationError),
+}
+
+/// Reason why a pod doesn't verify, see `Pod::verify_detailed`.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum VerifyError {
+    #[error("input pod {0} doesn't verify or doesn't match its statements")]
+    InputPod(PodId),
+    #[error(
+        "statement {index} copies {statement}, which isn't a public statement of an input pod"
+    )]
+    CopyNotInInput { index: usize, statement: String },
+    #[error("pod id doesn't match the public statements")]
+    IdMismatch,
+    #[error("missing pod type statement")]
+    MissingTypeStatement,
+    #[error("repeated ValueOf key")]
+    DuplicateValueOf,
+    #[error("statement {index} ({statement}) isn't justified by operation {operation}: {reason}")]
+    StatementNotJustified {
+        index: usize,
+        statement: String,
+        operation: String,
+        reason: String,
+    },
+    #[error("pod doesn't verify")]
+    Invalid,
+}
+
+pub type Result<T, E = Pod2Error> = std::result::Result<T, E>;
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::middleware::{
+        containers::Dictionary, hash_str, AnchoredKey, NativeOperation, Operation, Statement,
+        StatementArg, SELF,
+    };
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_pod2_error_variants() {
+        let key = Value(hash_str("a").0);
+        let dict = Dictionary::new(&HashMap::from([(hash_str("a"), Value::from(1))]));
+        let missing = Value(hash_str("b").0);
+        assert_eq!(dict.get(&missing), Err(Pod2Error::KeyNotFound(missing)));
+
+        let proof = dict.prove(&key).unwrap();
+        assert!(matches!(
+            Dictionary::verify(dict.commitment(), &proof, &key, &Value::from(2)),
+            Err(Pod2Error::ProofInvalid(_))
+        ));
+
+        let not_int = Value(hash_str("c").0);
+        let int: Result<i64> = not_int.try_into();
+        assert_eq!(int, Err(Pod2Error::NotAnInteger(not_int)));
+
+        assert!(matches!(
+            StatementArg::Literal(key).key(),
+            Err(Pod2Error::InvalidStatementArg { .. })
+        ));
+
+        let ak = AnchoredKey(SELF, hash_str("a"));
+        let err: Pod2Error = Operation::op(
+            NativeOperation::EqualFromEntries,
+            &[Statement::ValueOf(ak, key)],
+        )
+        .unwrap_err()
+        .into();
+        assert!(matches!(err, Pod2Error::Operation(_)));
+    }
+}
@@ -1,3 +1,4 @@
+use anyhow::Result;
 use std::collections::HashMap;
 
 use crate::backends::mock_signed::MockSigner;
@@ -24,7 +25,7 @@ pub fn zu_kyc_pod_builder(
     params: &Params,
     gov_id: &SignedPod,
     pay_stub: &SignedPod,
-) -> MainPodBuilder {
+) -> Result<MainPodBuilder> {
     let sanction_list = Value::Dictionary(Dictionary::new(&HashMap::new())); // empty dictionary
     let now_minus_18y: i64 = 1169909388;
     let now_minus_1y: i64 = 1706367566;
@@ -32,16 +33,16 @@ pub fn zu_kyc_pod_builder(
     let mut kyc = MainPodBuilder::new(params);
     kyc.add_signed_pod(&gov_id);
     kyc.add_signed_pod(&pay_stub);
-    kyc.pub_op(op!(not_contains, &sanction_list, (gov_id, "idNumber")));
-    kyc.pub_op(op!(lt, (gov_id, "dateOfBirth"), now_minus_18y));
+    kyc.pub_op(op!(not_contains, &sanction_list, (gov_id, "idNumber")))?;
+    kyc.pub_op(op!(lt, (gov_id, "dateOfBirth"), now_minus_18y))?;
     kyc.pub_op(op!(
         eq,
         (gov_id, "socialSecurityNumber"),
         (pay_stub, "socialSecurityNumber")
-    ));
-    kyc.pub_op(op!(eq, (pay_stub, "startDate"), now_minus_1y));
+    ))?;
+    kyc.pub_op(op!(eq, (pay_stub, "startDate"), now_minus_1y))?;
 
-    kyc
+    Ok(kyc)
 }
 
 // GreatBoy
@@ -67,7 +68,7 @@ pub fn great_boy_pod_builder(
     friend_pods: [&SignedPod; 2],
     good_boy_issuers: &Value,
     receiver: &str,
-) -> MainPodBuilder {
+) -> Result<MainPodBuilder> {
     // Attestment chain (issuer -> good boy -> great boy):
     // issuer 0 -> good_boy_pods[0] => good boy 0
     // issuer 1 -> good_boy_pods[1] => good boy 0
@@ -90,53 +91,52 @@ pub fn great_boy_pod_builder(
             eq,
             (friend_pods[good_boy_idx], KEY_TYPE),
             PodType::MockSigned as i64
-        ));
+        ))?;
         for issuer_idx in 0..2 {
             // Type check
             great_boy.pub_op(op!(
                 eq,
                 (good_boy_pods[good_boy_idx * 2 + issuer_idx], KEY_TYPE),
                 PodType::MockSigned as i64
-            ));
+            ))?;
             // Each good boy POD comes from a valid issuer
             great_boy.pub_op(op!(
                 contains,
                 good_boy_issuers,
                 (good_boy_pods[good_boy_idx * 2 + issuer_idx], KEY_SIGNER)
-            ));
+            ))?;
             // Each good boy has 2 good boy pods
             great_boy.pub_op(op!(
                 eq,
                 (good_boy_pods[good_boy_idx * 2 + issuer_idx], "user"),
                 (friend_pods[good_boy_idx], KEY_SIGNER)
-            ));
+            ))?;
         }
         // The good boy PODs from each good boy have different issuers
         great_boy.pub_op(op!(
             ne,
             (good_boy_pods[good_boy_idx * 2 + 0], KEY_SIGNER),
             (good_boy_pods[good_boy_idx * 2 + 1], KEY_SIGNER)
-        ));
+        ))?;
         // Each good boy is receivers' friend
-        great_boy.pub_op(op!(eq, (friend_pods[good_boy_idx], "friend"), receiver));
+        great_boy.pub_op(op!(eq, (friend_pods[good_boy_idx], "friend"), receiver))?;
     }
     // The two good boys are different
     great_boy.pub_op(op!(
         ne,
         (friend_pods[0], KEY_SIGNER),
         (friend_pods[1], KEY_SIGNER)
-    ));
+    ))?;
 
-    great_boy
+    Ok(great_boy)
 }
 
-pub fn great_boy_pod_full_flow() -> MainPodBuilder {
-    let params = Params {
-        max_input_signed_pods: 6,
-        max_statements: 100,
-        max_public_statements: 50,
-        ..Default::default()
-    };
+pub fn great_boy_pod_full_flow() -> Result<MainPodBuilder> {
+    let params = Params::builder()
+        .max_input_signed_pods(6)
+        .max_statements(100)
+        .max_public_statements(50)
+        .build();
 
     let good_boy_issuers = ["Giggles", "Macrosoft", "FaeBook"];
     let mut giggles_signer = MockSigner {
@@ -160,8 +160,8 @@ pub fn great_boy_pod_full_flow() -> MainPodBuilder {
     let mut bob_good_boys = Vec::new();
 
     let good_boy = good_boy_sign_pod_builder(&params, &bob, 36);
-    bob_good_boys.push(good_boy.sign(&mut giggles_signer).unwrap());
-    bob_good_boys.push(good_boy.sign(&mut macrosoft_signer).unwrap());
+    bob_good_boys.push(good_boy.sign(&mut giggles_signer)?);
+    bob_good_boys.push(good_boy.sign(&mut macrosoft_signer)?);
 
     // Charlie receives two good_boy pods from Macrosoft and Faebook
 
@@ -169,15 +169,15 @@ pub fn great_boy_pod_full_flow() -> MainPodBuilder {
     let mut charlie_good_boys = Vec::new();
 
     let good_boy = good_boy_sign_pod_builder(&params, &charlie, 27);
-    charlie_good_boys.push(good_boy.sign(&mut macrosoft_signer).unwrap());
-    charlie_good_boys.push(good_boy.sign(&mut faebook_signer).unwrap());
+    charlie_good_boys.push(good_boy.sign(&mut macrosoft_signer)?);
+    charlie_good_boys.push(good_boy.sign(&mut faebook_signer)?);
 
     // Bob and Charlie send Alice a Friend POD
 
     let mut alice_friend_pods = Vec::new();
     let friend = friend_sign_pod_builder(&params, &alice);
-    alice_friend_pods.push(friend.sign(&mut bob_signer).unwrap());
-    alice_friend_pods.push(friend.sign(&mut charlie_signer).unwrap());
+    alice_friend_pods.push(friend.sign(&mut bob_signer)?);
+    alice_friend_pods.push(friend.sign(&mut charlie_signer)?);
 
     let good_boy_issuers_dict = Value::Dictionary(Dictionary::new(&HashMap::new())); // empty
     great_boy_pod_builder(
@@ -214,25 +214,25 @@ pub fn tickets_pod_builder(
     expected_event_id: i64,
     expect_consumed: bool,
     blacklisted_emails: &Value,
-) -> MainPodBuilder {
+) -> Result<MainPodBuilder> {
     // Create a main pod referencing this signed pod with some statements
     let mut builder = MainPodBuilder::new(params);
     builder.add_signed_pod(signed_pod);
-    builder.pub_op(op!(eq, (signed_pod, "eventId"), expected_event_id));
-    builder.pub_op(op!(eq, (signed_pod, "isConsumed"), expect_consumed));
-    builder.pub_op(op!(eq, (signed_pod, "isRevoked"), false));
+    builder.pub_op(op!(eq, (signed_pod, "eventId"), expected_event_id))?;
+    builder.pub_op(op!(eq, (signed_pod, "isConsumed"), expect_consumed))?;
+    builder.pub_op(op!(eq, (signed_pod, "isRevoked"), false))?;
     builder.pub_op(op!(
         not_contains,
         blacklisted_emails,
         (signed_pod, "attendeeEmail")
-    ));
-    builder
+    ))?;
+    Ok(builder)
 }
 
-pub fn tickets_pod_full_flow() -> MainPodBuilder {
+pub fn tickets_pod_full_flow() -> Result<MainPodBuilder> {
     let params = Params::default();
     let builder = tickets_sign_pod_builder(&params);
-    let signed_pod = builder.sign(&mut MockSigner { pk: "test".into() }).unwrap();
+    let signed_pod = builder.sign(&mut MockSigner { pk: "test".into() })?;
     tickets_pod_builder(
         &params,
         &signed_pod,
@@ -4,18 +4,25 @@
 mod operation;
 mod statement;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use hex::FromHex;
 use itertools::Itertools;
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::json;
 use std::collections::HashMap;
 use std::convert::From;
 use std::fmt;
+use std::iter;
 
+use crate::backends;
+use crate::error::Pod2Error;
 use crate::middleware::{
     self,
     containers::{Array, Dictionary, Set},
-    hash_str, Hash, MainPodInputs, NativeOperation, NativeStatement, Params, PodId, PodProver,
-    PodSigner, SELF,
+    hash_str, hash_str_cached, Hash, MainPodInputs, NativeOperation, NativeStatement, Params,
+    PodId, PodProver, PodSigner, KEY_SIGNER, KEY_SIGNERS, KEY_TYPE, SELF,
 };
+use crate::primitives::merkletree::MerkleProof;
 pub use operation::*;
 pub use statement::*;
 
@@ -27,6 +34,56 @@ pub enum PodClass {
     Main,
 }
 
+/// Error returned when a [MainPodBuilder] doesn't fit in the limits given by its [Params].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BuilderError {
+    /// More statements than fit in the `params.max_priv_statements()` private slots.
+    TooManyStatements { count: usize, max: usize },
+    /// More public statements than fit in the `params.max_public_statements` slots.
+    TooManyPublicStatements { count: usize, max: usize },
+    /// A public statement that isn't among the derived statements.
+    PublicStatementNotDerived(Statement),
+    /// A statement created by `NewEntry` with a key that isn't anchored at `SELF`.
+    ForeignNewEntry(Statement),
+    /// Error from the middleware.
+    Pod2(Pod2Error),
+}
+
+impl fmt::Display for BuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuilderError::TooManyStatements { count, max } => {
+                write!(f, "Too many statements: {} (max {})", count, max)
+            }
+            BuilderError::TooManyPublicStatements { count, max } => {
+                write!(f, "Too many public statements: {} (max {})", count, max)
+            }
+            BuilderError::PublicStatementNotDerived(st) => {
+                write!(f, "Public statement {} wasn't derived", st)
+            }
+            BuilderError::ForeignNewEntry(st) => {
+                write!(f, "NewEntry statement {} isn't anchored at SELF", st)
+            }
+            BuilderError::Pod2(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for BuilderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuilderError::Pod2(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<Pod2Error> for BuilderError {
+    fn from(e: Pod2Error) -> Self {
+        BuilderError::Pod2(e)
+    }
+}
+
 // An Origin, which represents a reference to an ancestor POD.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Default)]
 pub struct Origin(pub PodClass, pub PodId);
@@ -42,6 +99,45 @@ pub enum Value {
     Raw(middleware::Value),
 }
 
+impl Value {
+    /// Parse a value with the grammar:
+    /// - `"..."`: `String` (no escapes, the quotes can't appear inside)
+    /// - `true` / `false`: `Bool`
+    /// - decimal integer, optionally negative: `Int`
+    /// - `0x` followed by 64 hex digits: `Raw` (as read by `Hash::from_hex`)
+    ///
+    /// Anything else, like an unquoted word, is rejected as ambiguous.
+    pub fn parse(s: &str) -> Result<Value> {
+        if let Some(string) = s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+            if string.contains('"') {
+                return Err(anyhow!("Unexpected quote in string {}.", s));
+            }
+            return Ok(Value::String(string.to_string()));
+        }
+        if let Some(hex) = s.strip_prefix("0x") {
+            let hash =
+                Hash::from_hex(hex).map_err(|e| anyhow!("Invalid raw value {}: {}.", s, e))?;
+            return Ok(Value::Raw(hash.into()));
+        }
+        match s {
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            _ => s
+                .parse::<i64>()
+                .map(Value::Int)
+                .map_err(|_| anyhow!("Ambiguous value {}, quote it if it's a string.", s)),
+        }
+    }
+}
+
+impl Value {
+    /// Whether the value is a `Dictionary`, `Set` or `Array`, as opposed to a scalar or a raw
+    /// value of unknown type.
+    pub fn is_container(&self) -> bool {
+        matches!(self, Value::Dictionary(_) | Value::Set(_) | Value::Array(_))
+    }
+}
+
 impl From<&str> for Value {
     fn from(s: &str) -> Self {
         Value::String(s.to_string())
@@ -74,6 +170,28 @@ impl From<&Value> for middleware::Value {
     }
 }
 
+/// Compares by lowering the frontend value, so a `String` equals the hash of the string and a
+/// container equals its commitment.
+impl PartialEq<middleware::Value> for Value {
+    fn eq(&self, other: &middleware::Value) -> bool {
+        middleware::Value::from(self) == *other
+    }
+}
+
+impl PartialEq<Value> for middleware::Value {
+    fn eq(&self, other: &Value) -> bool {
+        other == self
+    }
+}
+
+/// Hashes the variant and the lowered value, so that equal values have the same hash.
+impl std::hash::Hash for Value {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        middleware::Value::from(self).hash(state);
+    }
+}
+
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -106,18 +224,45 @@ impl SignedPodBuilder {
         self.kvs.insert(key.into(), value.into());
     }
 
+    /// Remove an entry, returning its value.  The reserved `KEY_TYPE`, `KEY_SIGNER` and
+    /// `KEY_SIGNERS` entries are managed by the signer and can't be removed, so `None` is
+    /// returned for them.
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        if key == KEY_TYPE || key == KEY_SIGNER || key == KEY_SIGNERS {
+            return None;
+        }
+        self.kvs.remove(key)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.kvs.contains_key(key)
+    }
+
+    /// Sign the inserted entries.  The signer adds the reserved `KEY_TYPE` and `KEY_SIGNER` (or
+    /// `KEY_SIGNERS`) entries to the pod, so it's an error to insert them manually.
     pub fn sign<S: PodSigner>(&self, signer: &mut S) -> Result<SignedPod> {
+        if let Some(key) = [KEY_TYPE, KEY_SIGNER, KEY_SIGNERS]
+            .into_iter()
+            .find(|key| self.kvs.contains_key(*key))
+        {
+            return Err(anyhow!("Key {} is reserved and set by the signer.", key));
+        }
         let mut kvs = HashMap::new();
-        let mut key_string_map = HashMap::new();
+        let mut key_string_map = HashMap::from(
+            [KEY_TYPE, KEY_SIGNER, KEY_SIGNERS].map(|key| (hash_str(key), key.to_string())),
+        );
+        let mut value_map = HashMap::new();
         for (k, v) in self.kvs.iter() {
-            let k_hash = hash_str(k);
+            let k_hash = hash_str_cached(k);
             kvs.insert(k_hash, middleware::Value::from(v));
             key_string_map.insert(k_hash, k.clone());
+            value_map.insert(k_hash, v.clone());
         }
         let pod = signer.sign(&self.params, &kvs)?;
         Ok(SignedPod {
             pod,
             key_string_map,
+            value_map,
         })
     }
 }
@@ -129,18 +274,28 @@ pub struct SignedPod {
     pub pod: Box<dyn middleware::Pod>,
     /// HashMap to store the reverse relation between key strings and key hashes
     pub key_string_map: HashMap<Hash, String>,
+    /// HashMap to store the frontend values inserted at each key hash
+    pub value_map: HashMap<Hash, Value>,
 }
 
 impl fmt::Display for SignedPod {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "SignedPod (id:{}):", self.id())?;
-        // Note: current version iterates sorting by keys of the kvs, but the merkletree defined at
-        // https://0xparc.github.io/pod2/merkletree.html will not need it since it will be
-        // deterministic based on the keys values not on the order of the keys when added into the
-        // tree.
-        for (k, v) in self.kvs().iter().sorted_by_key(|kv| kv.0) {
+        for (k, v) in self.iter() {
             writeln!(f, "  - {}: {}", k, v)?;
         }
+        // Entries without a typed value, like the ones set by the signer
+        for (k, v) in self
+            .kvs()
+            .iter()
+            .filter(|(k, _)| !self.value_map.contains_key(k))
+            .sorted_by_key(|kv| kv.0)
+        {
+            match self.key_string_map.get(k) {
+                Some(name) => writeln!(f, "  - {}: {}", name, v)?,
+                None => writeln!(f, "  - {}: {}", k, v)?,
+            }
+        }
         Ok(())
     }
 }
@@ -155,6 +310,34 @@ impl SignedPod {
     pub fn verify(&self) -> bool {
         self.pod.verify()
     }
+    /// Typed value at `key`.  Values without a known preimage (like the ones set by the signer)
+    /// are returned as `Value::Raw`.
+    pub fn get(&self, key: &str) -> Option<Value> {
+        let k_hash = hash_str(key);
+        let raw = self.kvs().get(&k_hash).cloned()?;
+        match self.value_map.get(&k_hash) {
+            Some(v) if middleware::Value::from(v) == raw => Some(v.clone()),
+            _ => Some(Value::Raw(raw)),
+        }
+    }
+    /// Value at the `KEY_SIGNER` key, set by the signer.
+    pub fn signer(&self) -> Option<Value> {
+        self.get(KEY_SIGNER)
+    }
+    /// Load a pod exported with [SignedPod::to_json].  Errors if the pod doesn't verify.
+    pub fn from_json(value: serde_json::Value) -> Result<SignedPod> {
+        serde_json::from_value(value).map_err(|e| anyhow!("Invalid signed pod: {}", e))
+    }
+    pub fn to_json(&self) -> Result<serde_json::Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+    /// Entries with a known key name and typed value, sorted by key name.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.key_string_map
+            .iter()
+            .filter_map(|(h, k)| self.value_map.get(h).map(|v| (k, v)))
+            .sorted_by_key(|(k, _)| *k)
+    }
     pub fn kvs(&self) -> HashMap<Hash, middleware::Value> {
         self.pod
             .kvs()
@@ -164,12 +347,61 @@ impl SignedPod {
     }
 }
 
+/// Serialized form of a [SignedPod]: the backend pod, as given by `Pod::to_json`, and the key
+/// names.
+#[derive(Serialize, Deserialize)]
+struct SignedPodData {
+    pod: serde_json::Value,
+    key_string_map: Vec<(Hash, String)>,
+}
+
+impl Serialize for SignedPod {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let pod = self
+            .pod
+            .to_json()
+            .ok_or_else(|| ser::Error::custom("unsupported signed pod backend"))?;
+        let mut key_string_map: Vec<_> = self
+            .key_string_map
+            .iter()
+            .map(|(h, k)| (*h, k.clone()))
+            .collect();
+        key_string_map.sort();
+        SignedPodData {
+            pod,
+            key_string_map,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// The typed values aren't serialized, so a deserialized [SignedPod] returns `Value::Raw` from
+/// [SignedPod::get].  The pod is verified on load.
+impl<'de> Deserialize<'de> for SignedPod {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = SignedPodData::deserialize(deserializer)?;
+        let pod = backends::signed_pod_from_json(data.pod).map_err(de::Error::custom)?;
+        let pod = SignedPod {
+            pod,
+            key_string_map: data.key_string_map.into_iter().collect(),
+            value_map: HashMap::new(),
+        };
+        if !pod.verify() {
+            return Err(de::Error::custom(format!(
+                "signed pod {} doesn't verify",
+                pod.id()
+            )));
+        }
+        Ok(pod)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct AnchoredKey(pub Origin, pub String);
 
 impl From<AnchoredKey> for middleware::AnchoredKey {
     fn from(ak: AnchoredKey) -> Self {
-        middleware::AnchoredKey(ak.0 .1, hash_str(&ak.1))
+        middleware::AnchoredKey(ak.0 .1, hash_str_cached(&ak.1))
     }
 }
 
@@ -183,6 +415,10 @@ pub struct MainPodBuilder {
     pub public_statements: Vec<Statement>,
     // Internal state
     const_cnt: usize,
+    /// Keys of the entries introduced for literal operands, to reuse them when a literal recurs.
+    /// Indexed by value and by whether the entry is public, so that a public operation doesn't
+    /// refer to a private entry.
+    const_keys: HashMap<(Value, bool), AnchoredKey>,
 }
 
 impl fmt::Display for MainPodBuilder {
@@ -216,13 +452,45 @@ impl MainPodBuilder {
             operations: Vec::new(),
             public_statements: Vec::new(),
             const_cnt: 0,
+            const_keys: HashMap::new(),
         }
     }
     pub fn add_signed_pod(&mut self, pod: &SignedPod) {
         self.input_signed_pods.push(pod.clone());
     }
-    pub fn add_main_pod(&mut self, pod: MainPod) {
+    /// Add an input main pod, returning its public statements so that they can be used as
+    /// operation arguments (for example with `op!(copy, ...)`).
+    pub fn add_main_pod(&mut self, pod: MainPod) -> Vec<Statement> {
+        let statements = pod.pub_statements();
         self.input_main_pods.push(pod);
+        statements
+    }
+    /// Copy `st` from the input pod `pod_id`, checking that it's one of its public statements.
+    pub fn copy_from(&mut self, public: bool, pod_id: PodId, st: &Statement) -> Result<Statement> {
+        let pod = self
+            .input_signed_pods
+            .iter()
+            .map(|pod| &pod.pod)
+            .chain(self.input_main_pods.iter().map(|pod| &pod.pod))
+            .find(|pod| pod.id() == pod_id)
+            .ok_or_else(|| anyhow!("{} isn't an input pod.", pod_id))?;
+        if !pod
+            .pub_statements()
+            .contains(&middleware::Statement::try_from(st.clone())?.normalize())
+        {
+            return Err(anyhow!(
+                "Statement {} isn't a public statement of {}.",
+                st,
+                pod_id
+            ));
+        }
+        self.op(
+            public,
+            Operation(
+                NativeOperation::CopyStatement,
+                vec![OperationArg::Statement(st.clone())],
+            ),
+        )
     }
     pub fn insert(&mut self, st_op: (Statement, Operation)) {
         let (st, op) = st_op;
@@ -231,27 +499,58 @@ impl MainPodBuilder {
     }
 
     /// Convert [OperationArg]s to [StatementArg]s for the operations that work with entries
-    fn op_args_entries(&mut self, public: bool, args: &mut [OperationArg]) -> Vec<StatementArg> {
+    fn op_args_entries(
+        &mut self,
+        public: bool,
+        args: &mut [OperationArg],
+    ) -> Result<Vec<StatementArg>> {
+        // Check all the arguments before introducing the entries for the literals, so that a
+        // failed operation doesn't leave them behind
+        if let Some(s) = args.iter().find_map(|arg| match arg {
+            OperationArg::Statement(s) if s.0 != NativeStatement::ValueOf => Some(s),
+            _ => None,
+        }) {
+            return Err(anyhow!(
+                "Invalid statement argument {}, expected a ValueOf statement.",
+                s
+            ));
+        }
         let mut st_args = Vec::new();
         for arg in args.iter_mut() {
             match arg {
-                OperationArg::Statement(s) => {
-                    if s.0 == NativeStatement::ValueOf {
-                        st_args.push(s.1[0].clone())
-                    } else {
-                        panic!("Invalid statement argument.");
-                    }
-                }
+                OperationArg::Statement(s) => st_args.push(s.1[0].clone()),
                 OperationArg::Literal(v) => {
-                    let k = format!("c{}", self.const_cnt);
-                    self.const_cnt += 1;
-                    let value_of_st = self.op(
-                        public,
-                        Operation(
-                            NativeOperation::NewEntry,
-                            vec![OperationArg::Entry(k.clone(), v.clone())],
+                    // A public entry can also be used by a private operation
+                    let cached = self.const_keys.get(&(v.clone(), public)).or_else(|| {
+                        (!public)
+                            .then(|| self.const_keys.get(&(v.clone(), true)))
+                            .flatten()
+                    });
+                    let value_of_st = match cached {
+                        Some(ak) => Statement(
+                            NativeStatement::ValueOf,
+                            vec![
+                                StatementArg::Key(ak.clone()),
+                                StatementArg::Literal(v.clone()),
+                            ],
                         ),
-                    );
+                        None => {
+                            let k = format!("c{}", self.const_cnt);
+                            self.const_cnt += 1;
+                            let value_of_st = self.op(
+                                public,
+                                Operation(
+                                    NativeOperation::NewEntry,
+                                    vec![OperationArg::Entry(k.clone(), v.clone())],
+                                ),
+                            )?;
+                            self.const_keys.insert(
+                                (v.clone(), public),
+                                AnchoredKey(Origin(PodClass::Main, SELF), k),
+                            );
+                            value_of_st
+                        }
+                    };
                     *arg = OperationArg::Statement(value_of_st.clone());
                     st_args.push(value_of_st.1[0].clone())
                 }
@@ -264,59 +563,373 @@ impl MainPodBuilder {
                 }
             };
         }
-        st_args
+        Ok(st_args)
+    }
+
+    /// Convert [OperationArg]s to [StatementArg]s for the operations that compare an entry
+    /// against `n` literals.  The literals are embedded in the output statement, so they're
+    /// removed from the operation arguments.
+    fn op_args_literal(
+        op_type: NativeOperation,
+        args: &mut Vec<OperationArg>,
+        n: usize,
+    ) -> Result<Vec<StatementArg>> {
+        let st_args = match args.split_first() {
+            Some((OperationArg::Statement(s), literals))
+                if s.0 == NativeStatement::ValueOf && literals.len() == n =>
+            {
+                iter::once(Some(s.1[0].clone()))
+                    .chain(literals.iter().map(|arg| match arg {
+                        OperationArg::Literal(v) => Some(StatementArg::Literal(v.clone())),
+                        _ => None,
+                    }))
+                    .collect::<Option<Vec<_>>>()
+            }
+            _ => None,
+        };
+        let st_args = st_args.ok_or_else(|| {
+            anyhow!(
+                "Invalid arguments for {:?}: {}",
+                op_type,
+                args.iter().join(", ")
+            )
+        })?;
+        args.truncate(1);
+        Ok(st_args)
+    }
+
+    /// Value held by an entry-like [OperationArg], if any
+    fn op_arg_value(arg: &OperationArg) -> Option<&Value> {
+        match arg {
+            OperationArg::Statement(Statement(NativeStatement::ValueOf, st_args)) => {
+                match st_args.get(1) {
+                    Some(StatementArg::Literal(v)) => Some(v),
+                    _ => None,
+                }
+            }
+            OperationArg::Statement(_) => None,
+            OperationArg::Literal(v) | OperationArg::Entry(_, v) => Some(v),
+        }
+    }
+
+    /// Check up front that the operand values have the types expected by the operation, so that
+    /// ill-typed operations fail at build time instead of at verification.
+    fn check_op_args_types(op_type: NativeOperation, args: &[OperationArg]) -> Result<()> {
+        use NativeOperation::*;
+        let values = args.iter().filter_map(Self::op_arg_value).collect_vec();
+        match op_type {
+            SumOf | ProductOf | MaxOf | DivOf | ModOf => {
+                for v in values {
+                    let int: Result<i64, _> = middleware::Value::from(v).try_into();
+                    if int.is_err() {
+                        return Err(anyhow!("{:?} expects integer operands, got {}", op_type, v));
+                    }
+                }
+            }
+            AndOf | OrOf | NotOf => {
+                for v in values {
+                    let int: Result<i64, _> = middleware::Value::from(v).try_into();
+                    if !matches!(int, Ok(0) | Ok(1)) {
+                        return Err(anyhow!("{:?} expects boolean operands, got {}", op_type, v));
+                    }
+                }
+            }
+            ContainsFromEntries | NotContainsFromEntries => {
+                if let Some(v @ (Value::String(_) | Value::Int(_) | Value::Bool(_))) =
+                    values.first()
+                {
+                    return Err(anyhow!(
+                        "{:?} expects a container as first operand, got {}",
+                        op_type,
+                        v
+                    ));
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Extract the statements of [OperationArg]s for the operations that derive a statement from
+    /// previous statements
+    fn op_args_statements(args: &[OperationArg]) -> Result<Vec<Statement>> {
+        args.iter()
+            .map(|arg| match arg {
+                OperationArg::Statement(s) => Ok(s.clone()),
+                _ => Err(anyhow!(
+                    "Invalid operation argument {}, expected a statement.",
+                    arg
+                )),
+            })
+            .collect()
+    }
+
+    /// From `Contains(ak1, ak2)` and `Equal(ak1, ak3)` (or `Equal(ak3, ak1)`) derive
+    /// `Contains(ak3, ak2)`
+    fn op_rename_contained_by(args: &[OperationArg]) -> Result<Statement> {
+        match Self::op_args_statements(args)?.as_slice() {
+            [Statement(NativeStatement::Contains, c_args), Statement(NativeStatement::Equal, e_args)]
+                if c_args.len() == 2
+                    && e_args.len() == 2
+                    && (c_args[0] == e_args[0] || c_args[0] == e_args[1]) =>
+            {
+                let renamed = if c_args[0] == e_args[0] {
+                    &e_args[1]
+                } else {
+                    &e_args[0]
+                };
+                Ok(Statement(
+                    NativeStatement::Contains,
+                    vec![renamed.clone(), c_args[1].clone()],
+                ))
+            }
+            _ => Err(anyhow!(
+                "Invalid arguments for RenameContainedBy: {}",
+                args.iter().join(", ")
+            )),
+        }
+    }
+
+    /// Outer keys of a chain of `Equal` links, taken in order and each in either direction, as
+    /// the middleware check does.  `None` if consecutive links don't share a key.
+    fn equal_chain_ends(
+        links: &[(StatementArg, StatementArg)],
+    ) -> Option<(StatementArg, StatementArg)> {
+        let (first, rest) = links.split_first()?;
+        [(&first.0, &first.1), (&first.1, &first.0)]
+            .into_iter()
+            .find_map(|(start, mut current)| {
+                for (ak1, ak2) in rest {
+                    current = if ak1 == current {
+                        ak2
+                    } else if ak2 == current {
+                        ak1
+                    } else {
+                        return None;
+                    };
+                }
+                Some((start.clone(), current.clone()))
+            })
+    }
+
+    /// From `Equal(ak1, ak2)` and `Equal(ak2, ak3)` derive `Equal(ak1, ak3)`.  The keys of each
+    /// `Equal` can be in either order.
+    fn op_transitive_equal(args: &[OperationArg]) -> Result<Statement> {
+        match Self::op_args_statements(args)?.as_slice() {
+            [Statement(NativeStatement::Equal, args1), Statement(NativeStatement::Equal, args2)]
+                if args1.len() == 2 && args2.len() == 2 =>
+            {
+                let links = [
+                    (args1[0].clone(), args1[1].clone()),
+                    (args2[0].clone(), args2[1].clone()),
+                ];
+                let (ak1, ak3) = Self::equal_chain_ends(&links).ok_or(anyhow!(
+                    "Equalities of transitive equality don't share a key: {}, {}",
+                    args[0],
+                    args[1]
+                ))?;
+                Ok(Statement(NativeStatement::Equal, vec![ak1, ak3]))
+            }
+            _ => Err(anyhow!(
+                "Invalid arguments for TransitiveEqualFromStatements: {}",
+                args.iter().join(", ")
+            )),
+        }
+    }
+
+    /// From `ValueOf(ak1, v)`, `ValueOf(ak2, dict)` and a literal key derive
+    /// `EqualsContainedValue(ak1, ak2, key)`.  The key is embedded in the output statement, so
+    /// it's removed from the operation arguments.
+    fn op_equals_contained_value(args: &mut Vec<OperationArg>) -> Result<Statement> {
+        let st = match args.as_slice() {
+            [OperationArg::Statement(value_of), OperationArg::Statement(dict), OperationArg::Literal(key)]
+                if value_of.0 == NativeStatement::ValueOf && dict.0 == NativeStatement::ValueOf =>
+            {
+                Statement(
+                    NativeStatement::EqualsContainedValue,
+                    vec![
+                        value_of.1[0].clone(),
+                        dict.1[0].clone(),
+                        StatementArg::Literal(key.clone()),
+                    ],
+                )
+            }
+            _ => {
+                return Err(anyhow!(
+                    "Invalid arguments for EqualsContainedValue: {}",
+                    args.iter().join(", ")
+                ))
+            }
+        };
+        args.truncate(2);
+        // Fail early if the entry can't be proved
+        MainPodCompiler::contained_value_proof(&st, args)?;
+        Ok(st)
+    }
+
+    /// Copy a single statement, typically from an input pod.
+    fn op_copy(args: &[OperationArg]) -> Result<Statement> {
+        match Self::op_args_statements(args)?.as_slice() {
+            [st] => Ok(st.clone()),
+            _ => Err(anyhow!(
+                "Expected a single statement to copy, got: {}",
+                args.iter().join(", ")
+            )),
+        }
+    }
+
+    /// From `Gt(ak1, ak2)` or `Lt(ak1, ak2)` (as given by `from`) derive `NotEqual(ak1, ak2)`
+    fn op_to_not_equal(from: NativeStatement, args: &[OperationArg]) -> Result<Statement> {
+        match Self::op_args_statements(args)?.as_slice() {
+            [Statement(code, st_args)] if *code == from && st_args.len() == 2 => {
+                Ok(Statement(NativeStatement::NotEqual, st_args.clone()))
+            }
+            _ => Err(anyhow!(
+                "Expected a single {:?} statement, got: {}",
+                from,
+                args.iter().join(", ")
+            )),
+        }
+    }
+
+    /// From a chain `Equal(ak1, ak2)`, `Equal(ak2, ak3)`, ..., `Equal(ak{n-1}, ak{n})` derive
+    /// `Equal(ak1, ak{n})`.  The keys of each link can be in either order.
+    fn op_transitive_equal_chain(&self, args: &[OperationArg]) -> Result<Statement> {
+        if args.len() > self.params.max_operation_args {
+            return Err(anyhow!(
+                "Chain of {} statements exceeds max_operation_args ({}).",
+                args.len(),
+                self.params.max_operation_args
+            ));
+        }
+        let links = Self::op_args_statements(args)?
+            .into_iter()
+            .map(|s| match s {
+                Statement(NativeStatement::Equal, st_args) if st_args.len() == 2 => {
+                    Ok((st_args[0].clone(), st_args[1].clone()))
+                }
+                _ => Err(anyhow!("Invalid statement {}, expected Equal.", s)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let (ak_first, ak_last) = Self::equal_chain_ends(&links)
+            .filter(|_| links.len() >= 2)
+            .ok_or(anyhow!("Statements don't form a chain of equalities."))?;
+        Ok(Statement(NativeStatement::Equal, vec![ak_first, ak_last]))
     }
 
-    pub fn pub_op(&mut self, op: Operation) -> Statement {
+    pub fn pub_op(&mut self, op: Operation) -> Result<Statement> {
         self.op(true, op)
     }
 
-    pub fn op(&mut self, public: bool, mut op: Operation) -> Statement {
+    pub fn op(&mut self, public: bool, mut op: Operation) -> Result<Statement> {
         use NativeOperation::*;
         let Operation(op_type, ref mut args) = op;
-        // TODO: argument type checking
+        Self::check_op_args_types(op_type, args)?;
         let st = match op_type {
             None => Statement(NativeStatement::None, vec![]),
-            NewEntry => Statement(NativeStatement::ValueOf, self.op_args_entries(public, args)),
-            CopyStatement => todo!(),
+            NewEntry => Statement(
+                NativeStatement::ValueOf,
+                self.op_args_entries(public, args)?,
+            ),
+            CopyStatement => Self::op_copy(args)?,
             EqualFromEntries => {
-                Statement(NativeStatement::Equal, self.op_args_entries(public, args))
+                Statement(NativeStatement::Equal, self.op_args_entries(public, args)?)
             }
             NotEqualFromEntries => Statement(
                 NativeStatement::NotEqual,
-                self.op_args_entries(public, args),
+                self.op_args_entries(public, args)?,
             ),
-            GtFromEntries => Statement(NativeStatement::Gt, self.op_args_entries(public, args)),
-            LtFromEntries => Statement(NativeStatement::Lt, self.op_args_entries(public, args)),
-            TransitiveEqualFromStatements => todo!(),
-            GtToNotEqual => todo!(),
-            LtToNotEqual => todo!(),
+            GtFromEntries => Statement(NativeStatement::Gt, self.op_args_entries(public, args)?),
+            LtFromEntries => Statement(NativeStatement::Lt, self.op_args_entries(public, args)?),
+            GteqFromEntries => {
+                Statement(NativeStatement::Gteq, self.op_args_entries(public, args)?)
+            }
+            LteqFromEntries => {
+                Statement(NativeStatement::Lteq, self.op_args_entries(public, args)?)
+            }
+            TransitiveEqualFromStatements => Self::op_transitive_equal(args)?,
+            GtToNotEqual => Self::op_to_not_equal(NativeStatement::Gt, args)?,
+            LtToNotEqual => Self::op_to_not_equal(NativeStatement::Lt, args)?,
             ContainsFromEntries => Statement(
                 NativeStatement::Contains,
-                self.op_args_entries(public, args),
+                self.op_args_entries(public, args)?,
             ),
             NotContainsFromEntries => Statement(
                 NativeStatement::NotContains,
-                self.op_args_entries(public, args),
+                self.op_args_entries(public, args)?,
+            ),
+            RenameContainedBy => Self::op_rename_contained_by(args)?,
+            SumOf => Statement(NativeStatement::SumOf, self.op_args_entries(public, args)?),
+            ProductOf => Statement(
+                NativeStatement::ProductOf,
+                self.op_args_entries(public, args)?,
+            ),
+            MaxOf => Statement(NativeStatement::MaxOf, self.op_args_entries(public, args)?),
+            EqualToLiteral => Statement(
+                NativeStatement::EqualLiteral,
+                Self::op_args_literal(op_type, args, 1)?,
+            ),
+            InRangeFromEntries => Statement(
+                NativeStatement::InRange,
+                Self::op_args_literal(op_type, args, 2)?,
+            ),
+            EqualsContainedValue => Self::op_equals_contained_value(args)?,
+            GtToLiteral => Statement(
+                NativeStatement::GtLiteral,
+                Self::op_args_literal(op_type, args, 1)?,
+            ),
+            LtToLiteral => Statement(
+                NativeStatement::LtLiteral,
+                Self::op_args_literal(op_type, args, 1)?,
             ),
-            RenameContainedBy => todo!(),
-            SumOf => todo!(),
-            ProductOf => todo!(),
-            MaxOf => todo!(),
+            TransitiveEqualFromChain => self.op_transitive_equal_chain(args)?,
+            DivOf => Statement(NativeStatement::DivOf, self.op_args_entries(public, args)?),
+            ModOf => Statement(NativeStatement::ModOf, self.op_args_entries(public, args)?),
+            AndOf => Statement(NativeStatement::AndOf, self.op_args_entries(public, args)?),
+            OrOf => Statement(NativeStatement::OrOf, self.op_args_entries(public, args)?),
+            NotOf => Statement(NativeStatement::NotOf, self.op_args_entries(public, args)?),
         };
         self.operations.push(op);
         if public {
             self.public_statements.push(st.clone());
         }
         self.statements.push(st);
-        self.statements[self.statements.len() - 1].clone()
+        Ok(self.statements[self.statements.len() - 1].clone())
     }
 
-    pub fn reveal(&mut self, st: &Statement) {
+    /// Derive `Contains(container, element)` from an entry known to hold a container value,
+    /// like the `ValueOf` of a signed pod entry holding a `Dictionary`, `Set` or `Array`.
+    pub fn contains(
+        &mut self,
+        public: bool,
+        container: impl Into<OperationArg>,
+        element: impl Into<OperationArg>,
+    ) -> Result<Statement> {
+        let container = container.into();
+        if !Self::op_arg_value(&container).is_some_and(Value::is_container) {
+            return Err(anyhow!("{} doesn't hold a container value.", container));
+        }
+        self.op(
+            public,
+            Operation(
+                NativeOperation::ContainsFromEntries,
+                vec![container, element.into()],
+            ),
+        )
+    }
+
+    /// Make public a statement that was previously derived in this builder.
+    pub fn reveal(&mut self, st: &Statement) -> Result<()> {
+        if !self.statements.contains(st) {
+            return Err(anyhow!("Can't reveal statement {}, it wasn't derived.", st));
+        }
         self.public_statements.push(st.clone());
+        Ok(())
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn prove<P: PodProver>(&self, prover: &mut P) -> Result<MainPod> {
+        self.params.validate()?;
         let compiler = MainPodCompiler::new(&self.params);
         let inputs = MainPodCompilerInputs {
             // signed_pods: &self.input_signed_pods,
@@ -335,14 +948,36 @@ impl MainPodBuilder {
             public_statements: &public_statements,
         };
         let pod = prover.prove(&self.params, inputs)?;
-        Ok(MainPod { pod })
+
+        // Remember the key names and origin classes used by this builder so that they can be
+        // recovered from the public statements of the proved pod.
+        let mut key_string_map =
+            HashMap::from([KEY_TYPE, KEY_SIGNER].map(|key| (hash_str(key), key.to_string())));
+        let mut pod_class_map = HashMap::new();
+        for st in self.statements.iter() {
+            for arg in st.1.iter() {
+                if let StatementArg::Key(AnchoredKey(origin, key)) = arg {
+                    key_string_map.insert(hash_str_cached(key), key.clone());
+                    pod_class_map.insert(origin.1, origin.0.clone());
+                }
+            }
+        }
+        pod_class_map.insert(pod.id(), PodClass::Main);
+        Ok(MainPod {
+            pod,
+            key_string_map,
+            pod_class_map,
+        })
     }
 }
 
 #[derive(Debug)]
 pub struct MainPod {
     pub pod: Box<dyn middleware::Pod>,
-    // TODO: metadata
+    /// HashMap to store the reverse relation between key strings and key hashes
+    pub key_string_map: HashMap<Hash, String>,
+    /// HashMap to store the class of the pods referenced by the statements
+    pub pod_class_map: HashMap<PodId, PodClass>,
 }
 
 impl MainPod {
@@ -352,6 +987,130 @@ impl MainPod {
     pub fn origin(&self) -> Origin {
         Origin(PodClass::Main, self.id())
     }
+    pub fn verify(&self) -> bool {
+        self.pod.verify()
+    }
+    /// Public statements of the pod, recovering key names and pod classes where they are known.
+    /// Unknown key names are displayed as their hash.
+    pub fn pub_statements(&self) -> Vec<Statement> {
+        self.pod
+            .pub_statements()
+            .into_iter()
+            .filter(|st| !st.is_none())
+            .map(|st| {
+                let args = st
+                    .args()
+                    .into_iter()
+                    .filter_map(|arg| match arg {
+                        middleware::StatementArg::None => None,
+                        middleware::StatementArg::Literal(v) => {
+                            Some(StatementArg::Literal(Value::Raw(v)))
+                        }
+                        middleware::StatementArg::Key(middleware::AnchoredKey(pod_id, h)) => {
+                            let class = self.pod_class_map.get(&pod_id).cloned();
+                            let key = self.key_string_map.get(&h).cloned();
+                            Some(StatementArg::Key(AnchoredKey(
+                                Origin(class.unwrap_or_default(), pod_id),
+                                key.unwrap_or_else(|| format!("{}", h)),
+                            )))
+                        }
+                    })
+                    .collect();
+                Statement(st.code(), args)
+            })
+            .collect()
+    }
+    /// Value at the `_type` key of the pod.
+    fn pod_type(&self) -> Option<middleware::Value> {
+        self.pod
+            .kvs()
+            .get(&middleware::AnchoredKey(self.id(), hash_str(KEY_TYPE)))
+            .cloned()
+    }
+    /// Input pods referenced by the statements, sorted by id.
+    fn input_pods(&self) -> Vec<(PodId, PodClass)> {
+        let id = self.id();
+        self.pod_class_map
+            .iter()
+            .filter(|(pod_id, _)| **pod_id != id && **pod_id != SELF)
+            .map(|(pod_id, class)| (*pod_id, class.clone()))
+            .sorted_by_key(|(pod_id, _)| pod_id.0)
+            .collect()
+    }
+    /// Public statements, except the one setting the pod `_type`.
+    fn pub_statements_without_type(&self) -> Vec<Statement> {
+        let id = self.id();
+        self.pub_statements()
+            .into_iter()
+            .filter(|st| {
+                !matches!(st.1.first(), Some(StatementArg::Key(AnchoredKey(origin, key)))
+                    if st.0 == NativeStatement::ValueOf && origin.1 == id && key == KEY_TYPE)
+            })
+            .collect()
+    }
+    /// Anchored keys referenced by the statements of the pod, see `Pod::referenced_keys`.
+    pub fn referenced_keys(&self) -> Vec<middleware::AnchoredKey> {
+        self.pod.referenced_keys()
+    }
+    /// GraphViz DOT rendering of how the pod's statements derive from each other.  Errors if
+    /// the backend doesn't support it.
+    pub fn to_graphviz(&self) -> Result<String> {
+        self.pod
+            .to_graphviz()
+            .ok_or_else(|| anyhow!("GraphViz export isn't supported by this pod backend."))
+    }
+    /// JSON document describing the pod: its id and type, the ids of the input pods referenced
+    /// by its statements, and its public statements with the key names resolved.
+    pub fn to_json(&self) -> serde_json::Value {
+        let input_pods: Vec<_> = self
+            .input_pods()
+            .into_iter()
+            .map(|(pod_id, class)| json!({ "id": pod_id, "class": format!("{:?}", class) }))
+            .collect();
+        let statements: Vec<_> = self
+            .pub_statements_without_type()
+            .into_iter()
+            .map(|st| {
+                let args: Vec<_> =
+                    st.1.iter()
+                        .map(|arg| match arg {
+                            StatementArg::Literal(v) => {
+                                json!({ "literal": middleware::Value::from(v) })
+                            }
+                            StatementArg::Key(AnchoredKey(origin, key)) => {
+                                json!({ "pod": origin.1, "key": key })
+                            }
+                        })
+                        .collect();
+                json!({ "predicate": st.0, "args": args })
+            })
+            .collect();
+        json!({
+            "id": self.id(),
+            "type": self.pod_type(),
+            "input_pods": input_pods,
+            "public_statements": statements,
+        })
+    }
+}
+
+impl fmt::Display for MainPod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "MainPod (id:{}):", self.id())?;
+        match self.pod_type() {
+            Some(pod_type) => writeln!(f, "  type: {}", pod_type)?,
+            None => writeln!(f, "  type: none")?,
+        }
+        writeln!(f, "  input_pods:")?;
+        for (pod_id, class) in self.input_pods() {
+            writeln!(f, "    - {} ({:?})", pod_id, class)?;
+        }
+        writeln!(f, "  public_statements:")?;
+        for st in self.pub_statements_without_type() {
+            writeln!(f, "    - {}", st)?;
+        }
+        Ok(())
+    }
 }
 
 struct MainPodCompilerInputs<'a> {
@@ -383,43 +1142,84 @@ impl MainPodCompiler {
         self.operations.push(op);
     }
 
-    fn compile_op_arg(&self, op_arg: &OperationArg) -> Option<middleware::Statement> {
+    fn compile_op_arg(&self, op_arg: &OperationArg) -> Result<Option<middleware::Statement>> {
         match op_arg {
-            OperationArg::Statement(s) => Some(self.compile_st(s)),
-            OperationArg::Literal(_v) => {
-                // OperationArg::Literal is a syntax sugar for the frontend.  This is translated to
-                // a new ValueOf statement and it's key used instead.
-                unreachable!()
+            OperationArg::Statement(s) => Ok(Some(self.compile_st(s)?)),
+            OperationArg::Literal(v) => {
+                // OperationArg::Literal is a syntax sugar for the frontend.  The builder
+                // translates it to a new ValueOf statement and uses its key instead, so a literal
+                // reaching the compiler means the operation didn't go through the builder.
+                Err(anyhow!(
+                    "Literal operation argument {} must be replaced by a ValueOf statement before \
+                     compiling.",
+                    v
+                ))
             }
             OperationArg::Entry(_k, _v) => {
                 // OperationArg::Entry is only used in the frontend.  The (key, value) will only
                 // appear in the ValueOf statement in the backend.  This is because a new ValueOf
                 // statement doesn't have any requirement on the key and value.
-                None
+                Ok(None)
             }
         }
     }
 
-    fn compile_st(&self, st: &Statement) -> middleware::Statement {
-        st.clone().try_into().unwrap()
+    fn compile_st(&self, st: &Statement) -> Result<middleware::Statement> {
+        Ok(middleware::Statement::try_from(st.clone())?.normalize())
+    }
+
+    /// Merkle proof of the dictionary entry behind an `EqualsContainedValue` statement.  The
+    /// dictionary argument must hold the full `Value::Dictionary`, not only its commitment.
+    fn contained_value_proof(st: &Statement, op_args: &[OperationArg]) -> Result<MerkleProof> {
+        let dict = match op_args.get(1) {
+            Some(OperationArg::Statement(Statement(NativeStatement::ValueOf, args))) => args.get(1),
+            _ => None,
+        };
+        match (dict, st.1.get(2)) {
+            (
+                Some(StatementArg::Literal(Value::Dictionary(dict))),
+                Some(StatementArg::Literal(key)),
+            ) => Ok(dict.prove(&key.into())?),
+            _ => Err(anyhow!(
+                "EqualsContainedValue needs a dictionary value to prove {}",
+                st
+            )),
+        }
     }
 
-    fn compile_op(&self, op: &Operation) -> middleware::Operation {
-        // TODO
+    fn compile_op(&self, st: &Statement, op: &Operation) -> Result<middleware::Operation> {
         let mop_code: middleware::NativeOperation = op.0.into();
         let mop_args =
             op.1.iter()
-                .flat_map(|arg| self.compile_op_arg(arg).map(|s| s.try_into().unwrap()))
-                .collect::<Vec<middleware::Statement>>();
-        middleware::Operation::op(mop_code, &mop_args).unwrap()
+                .map(|arg| self.compile_op_arg(arg))
+                .filter_map(|arg| arg.transpose())
+                .collect::<Result<Vec<middleware::Statement>>>()?;
+        if mop_code == NativeOperation::EqualsContainedValue {
+            let proof = Self::contained_value_proof(st, &op.1)?;
+            return Ok(middleware::Operation::op_with_proof(
+                mop_code, &mop_args, proof,
+            )?);
+        }
+        Ok(middleware::Operation::op(mop_code, &mop_args)?)
     }
 
-    fn compile_st_op(&mut self, st: &Statement, op: &Operation) {
-        let middle_st = self.compile_st(st);
-        let middle_op = self.compile_op(op);
+    fn compile_st_op(&mut self, st: &Statement, op: &Operation) -> Result<()> {
+        if op.0 == NativeOperation::NewEntry {
+            let self_key = match st.1.first() {
+                Some(StatementArg::Key(AnchoredKey(origin, _))) => origin.1 == SELF,
+                _ => false,
+            };
+            if !self_key {
+                return Err(BuilderError::ForeignNewEntry(st.clone()).into());
+            }
+        }
+        let middle_st = self.compile_st(st)?;
+        let middle_op = self.compile_op(st, op)?;
         self.push_st_op(middle_st, middle_op);
+        Ok(())
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn compile<'a>(
         mut self,
         inputs: MainPodCompilerInputs<'a>,
@@ -435,16 +1235,32 @@ impl MainPodCompiler {
             operations,
             public_statements,
         } = inputs;
-        for (st, op) in statements.iter().zip_eq(operations.iter()) {
-            self.compile_st_op(st, op);
-            if self.statements.len() > self.params.max_statements {
-                panic!("too many statements");
+        if statements.len() > self.params.max_priv_statements() {
+            return Err(BuilderError::TooManyStatements {
+                count: statements.len(),
+                max: self.params.max_priv_statements(),
             }
+            .into());
+        }
+        // One public slot is taken by the pod type statement.
+        if public_statements.len() >= self.params.max_public_statements {
+            return Err(BuilderError::TooManyPublicStatements {
+                count: public_statements.len(),
+                max: self.params.max_public_statements.saturating_sub(1),
+            }
+            .into());
+        }
+        if let Some(st) = public_statements.iter().find(|st| !statements.contains(st)) {
+            return Err(BuilderError::PublicStatementNotDerived(st.clone()).into());
+        }
+        for (st, op) in statements.iter().zip_eq(operations.iter()) {
+            trace_event!(debug, statement = %st, operation = %op, "compiling statement");
+            self.compile_st_op(st, op)?;
         }
         let public_statements = public_statements
             .iter()
             .map(|st| self.compile_st(st))
-            .collect_vec();
+            .collect::<Result<Vec<_>>>()?;
         Ok((self.statements, self.operations, public_statements))
     }
 }
@@ -456,40 +1272,147 @@ impl MainPodCompiler {
 pub mod build_utils {
     #[macro_export]
     macro_rules! op_args {
-        ($($arg:expr),+) => {vec![$(crate::frontend::OperationArg::from($arg)),*]}
+        ($($arg:expr),+) => {vec![$($crate::frontend::OperationArg::from($arg)),*]}
     }
 
+    /// Builds a frontend `Operation` from a short operation name and its arguments.
+    ///
+    /// ```
+    /// use pod2::frontend::MainPodBuilder;
+    /// use pod2::middleware::{NativeOperation, NativeStatement, Params};
+    /// use pod2::op;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let params = Params::default();
+    /// let mut builder = MainPodBuilder::new(&params);
+    /// let a = builder.op(false, op!(new_entry, ("a", 6)))?;
+    /// let b = builder.op(false, op!(new_entry, ("b", 2)))?;
+    /// let c = builder.op(false, op!(new_entry, ("c", 3)))?;
+    /// let d = builder.op(false, op!(new_entry, ("d", 5)))?;
+    /// let e = builder.op(false, op!(new_entry, ("e", 6)))?;
+    /// assert_eq!(a.0, NativeStatement::ValueOf);
+    ///
+    /// let st = builder.op(false, op!(sum, &d, &b, &c))?;
+    /// assert_eq!(st.0, NativeStatement::SumOf);
+    /// let st = builder.op(false, op!(product, &a, &b, &c))?;
+    /// assert_eq!(st.0, NativeStatement::ProductOf);
+    /// let st = builder.op(false, op!(max, &c, &b, &c))?;
+    /// assert_eq!(st.0, NativeStatement::MaxOf);
+    ///
+    /// let ae = builder.op(false, op!(eq, &a, &e))?;
+    /// let ea = builder.op(false, op!(eq, &e, &a))?;
+    /// let st = builder.op(false, op!(transitive_eq, &ae, &ea))?;
+    /// assert_eq!(st.0, NativeStatement::Equal);
+    ///
+    /// let gt = builder.op(false, op!(gt, &a, &b))?;
+    /// let st = builder.op(false, op!(gt_to_ne, &gt))?;
+    /// assert_eq!(st.0, NativeStatement::NotEqual);
+    /// let lt = builder.op(false, op!(lt, &b, &a))?;
+    /// let st = builder.op(false, op!(lt_to_ne, &lt))?;
+    /// assert_eq!(st.0, NativeStatement::NotEqual);
+    ///
+    /// let copy = op!(copy, &gt);
+    /// assert_eq!(copy.0, NativeOperation::CopyStatement);
+    /// # Ok(())
+    /// # }
+    /// ```
     #[macro_export]
     macro_rules! op {
-        (eq, $($arg:expr),+) => { crate::frontend::Operation(
-            crate::middleware::NativeOperation::EqualFromEntries,
-            crate::op_args!($($arg),*)) };
-        (ne, $($arg:expr),+) => { crate::frontend::Operation(
-            crate::middleware::NativeOperation::NotEqualFromEntries,
-            crate::op_args!($($arg),*)) };
-        (gt, $($arg:expr),+) => { crate::frontend::Operation(
-            crate::middleware::NativeOperation::GtFromEntries,
-            crate::op_args!($($arg),*)) };
-        (lt, $($arg:expr),+) => { crate::frontend::Operation(
-            crate::middleware::NativeOperation::LtFromEntries,
-            crate::op_args!($($arg),*)) };
-        (contains, $($arg:expr),+) => { crate::frontend::Operation(
-            crate::middleware::NativeOperation::ContainsFromEntries,
-            crate::op_args!($($arg),*)) };
-        (not_contains, $($arg:expr),+) => { crate::frontend::Operation(
-            crate::middleware::NativeOperation::NotContainsFromEntries,
-            crate::op_args!($($arg),*)) };
+        (eq, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::EqualFromEntries,
+            $crate::op_args!($($arg),*)) };
+        (ne, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::NotEqualFromEntries,
+            $crate::op_args!($($arg),*)) };
+        (gt, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::GtFromEntries,
+            $crate::op_args!($($arg),*)) };
+        (lt, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::LtFromEntries,
+            $crate::op_args!($($arg),*)) };
+        (gteq, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::GteqFromEntries,
+            $crate::op_args!($($arg),*)) };
+        (lteq, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::LteqFromEntries,
+            $crate::op_args!($($arg),*)) };
+        (eq_literal, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::EqualToLiteral,
+            $crate::op_args!($($arg),*)) };
+        (in_range, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::InRangeFromEntries,
+            $crate::op_args!($($arg),*)) };
+        (gt_literal, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::GtToLiteral,
+            $crate::op_args!($($arg),*)) };
+        (lt_literal, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::LtToLiteral,
+            $crate::op_args!($($arg),*)) };
+        (eq_contained_value, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::EqualsContainedValue,
+            $crate::op_args!($($arg),*)) };
+        (rename, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::RenameContainedBy,
+            $crate::op_args!($($arg),*)) };
+        (div, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::DivOf,
+            $crate::op_args!($($arg),*)) };
+        (mod, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::ModOf,
+            $crate::op_args!($($arg),*)) };
+        (and, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::AndOf,
+            $crate::op_args!($($arg),*)) };
+        (or, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::OrOf,
+            $crate::op_args!($($arg),*)) };
+        (not, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::NotOf,
+            $crate::op_args!($($arg),*)) };
+        (sum, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::SumOf,
+            $crate::op_args!($($arg),*)) };
+        (product, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::ProductOf,
+            $crate::op_args!($($arg),*)) };
+        (max, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::MaxOf,
+            $crate::op_args!($($arg),*)) };
+        (transitive_eq, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::TransitiveEqualFromStatements,
+            $crate::op_args!($($arg),*)) };
+        (gt_to_ne, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::GtToNotEqual,
+            $crate::op_args!($($arg),*)) };
+        (lt_to_ne, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::LtToNotEqual,
+            $crate::op_args!($($arg),*)) };
+        (copy, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::CopyStatement,
+            $crate::op_args!($($arg),*)) };
+        (new_entry, ($key:expr, $value:expr)) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::NewEntry,
+            vec![$crate::frontend::OperationArg::Entry(
+                String::from($key), $crate::frontend::Value::from($value))]) };
+        (contains, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::ContainsFromEntries,
+            $crate::op_args!($($arg),*)) };
+        (not_contains, $($arg:expr),+) => { $crate::frontend::Operation(
+            $crate::middleware::NativeOperation::NotContainsFromEntries,
+            $crate::op_args!($($arg),*)) };
     }
 }
 
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use crate::backends::mock_main::{MockProver, VALUE_TYPE};
     use crate::backends::mock_signed::MockSigner;
     use crate::examples::{
-        great_boy_pod_full_flow, tickets_pod_full_flow, zu_kyc_pod_builder,
-        zu_kyc_sign_pod_builders,
+        great_boy_pod_full_flow, tickets_pod_full_flow, tickets_sign_pod_builder,
+        zu_kyc_pod_builder, zu_kyc_sign_pod_builders,
     };
+    use crate::middleware::PodType;
 
     #[test]
     fn test_front_zu_kyc() -> Result<()> {
@@ -510,17 +1433,27 @@ pub mod tests {
         let pay_stub = pay_stub.sign(&mut signer).unwrap();
         println!("{}", pay_stub);
 
-        let kyc = zu_kyc_pod_builder(&params, &gov_id, &pay_stub);
+        let kyc = zu_kyc_pod_builder(&params, &gov_id, &pay_stub)?;
         println!("{}", kyc);
 
-        // TODO: prove kyc with MockProver and print it
+        let kyc = kyc.prove(&mut MockProver::default())?;
+        let printed = format!("{}", kyc);
+        println!("{}", printed);
+        assert!(printed.contains(&format!("{}", kyc.id())));
+        assert!(printed.contains("dateOfBirth"));
+        let printed_statements = printed
+            .lines()
+            .skip_while(|l| !l.starts_with("  public_statements:"))
+            .skip(1)
+            .count();
+        assert_eq!(printed_statements, 4);
 
         Ok(())
     }
 
     #[test]
     fn test_front_great_boy() -> Result<()> {
-        let great_boy = great_boy_pod_full_flow();
+        let great_boy = great_boy_pod_full_flow()?;
         println!("{}", great_boy);
 
         // TODO: prove kyc with MockProver and print it
@@ -530,9 +1463,1268 @@ pub mod tests {
 
     #[test]
     fn test_front_tickets() -> Result<()> {
-        let builder = tickets_pod_full_flow();
+        let builder = tickets_pod_full_flow()?;
         println!("{}", builder);
 
         Ok(())
     }
+
+    #[test]
+    fn test_front_gteq_lteq() -> Result<()> {
+        let params = Params::default();
+        let mut builder = SignedPodBuilder::new(&params);
+        builder.insert("age", 18);
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let pod = builder.sign(&mut signer)?;
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&pod);
+        // Boundary case: 18 >= 18 and 18 <= 18
+        let st = builder.pub_op(op!(gteq, (&pod, "age"), 18))?;
+        assert_eq!(st.0, NativeStatement::Gteq);
+        let st = builder.pub_op(op!(lteq, (&pod, "age"), 18))?;
+        assert_eq!(st.0, NativeStatement::Lteq);
+
+        let mut prover = MockProver::default();
+        let main_pod = builder.prove(&mut prover)?;
+        assert!(main_pod.pod.verify());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_equal_to_literal() -> Result<()> {
+        let params = Params::default();
+        let mut builder = SignedPodBuilder::new(&params);
+        builder.insert("country", "US");
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let gov_id = builder.sign(&mut signer)?;
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&gov_id);
+        let st = builder.pub_op(op!(eq_literal, (&gov_id, "country"), "US"))?;
+        assert_eq!(
+            st,
+            Statement(
+                NativeStatement::EqualLiteral,
+                vec![
+                    StatementArg::Key(AnchoredKey(gov_id.origin(), "country".into())),
+                    StatementArg::Literal(Value::from("US")),
+                ]
+            )
+        );
+        // No constant entry is introduced for the literal
+        assert_eq!(builder.statements.len(), 1);
+
+        let mut prover = MockProver::default();
+        let main_pod = builder.prove(&mut prover)?;
+        assert!(main_pod.pod.verify());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_gt_lt_literal() -> Result<()> {
+        let params = Params::default();
+        let (gov_id, _) = zu_kyc_sign_pod_builders(&params);
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let gov_id = gov_id.sign(&mut signer)?;
+        let now_minus_18y: i64 = 1169909388;
+        let mut prover = MockProver::default();
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&gov_id);
+        let st = builder.pub_op(op!(lt_literal, (&gov_id, "dateOfBirth"), now_minus_18y))?;
+        assert_eq!(
+            st,
+            Statement(
+                NativeStatement::LtLiteral,
+                vec![
+                    StatementArg::Key(AnchoredKey(gov_id.origin(), "dateOfBirth".into())),
+                    StatementArg::Literal(Value::from(now_minus_18y)),
+                ]
+            )
+        );
+        builder.pub_op(op!(gt_literal, (&gov_id, "dateOfBirth"), 0))?;
+        // No ValueOf statement is introduced for the constants
+        assert_eq!(builder.statements.len(), 2);
+        assert!(builder
+            .statements
+            .iter()
+            .all(|st| st.0 != NativeStatement::ValueOf));
+        assert!(builder.prove(&mut prover)?.verify());
+
+        // The comparison doesn't hold
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&gov_id);
+        builder.pub_op(op!(gt_literal, (&gov_id, "dateOfBirth"), now_minus_18y))?;
+        assert!(!builder.prove(&mut prover)?.verify());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_in_range() -> Result<()> {
+        let params = Params::default();
+        let mut builder = SignedPodBuilder::new(&params);
+        builder.insert("age", 30);
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let gov_id = builder.sign(&mut signer)?;
+        let mut prover = MockProver::default();
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&gov_id);
+        let st = builder.pub_op(op!(in_range, (&gov_id, "age"), 18, 120))?;
+        assert_eq!(
+            st,
+            Statement(
+                NativeStatement::InRange,
+                vec![
+                    StatementArg::Key(AnchoredKey(gov_id.origin(), "age".into())),
+                    StatementArg::Literal(Value::from(18)),
+                    StatementArg::Literal(Value::from(120)),
+                ]
+            )
+        );
+        // A single statement, without constant entries for the bounds
+        assert_eq!(builder.statements.len(), 1);
+        assert!(builder.prove(&mut prover)?.verify());
+
+        // below min and above max
+        for (min, max) in [(31i64, 120i64), (18, 29)] {
+            let mut builder = MainPodBuilder::new(&params);
+            builder.add_signed_pod(&gov_id);
+            builder.pub_op(op!(in_range, (&gov_id, "age"), min, max))?;
+            assert!(!builder.prove(&mut prover)?.verify());
+        }
+
+        // Both bounds are required
+        let mut builder = MainPodBuilder::new(&params);
+        assert!(builder.pub_op(op!(in_range, (&gov_id, "age"), 18)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_equals_contained_value() -> Result<()> {
+        let params = Params::default();
+        let mut builder = SignedPodBuilder::new(&params);
+        builder.insert("name", "alice");
+        builder.insert("country", "US");
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let gov_id = builder.sign(&mut signer)?;
+        let mut prover = MockProver::default();
+
+        let citizens = Value::Dictionary(Dictionary::new(&HashMap::from([
+            (
+                hash_str("alice"),
+                middleware::Value::from(&Value::from("US")),
+            ),
+            (hash_str("bob"), middleware::Value::from(&Value::from("FR"))),
+        ])));
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&gov_id);
+        let dict = builder.op(false, op!(new_entry, ("citizens", citizens.clone())))?;
+        let st = builder.pub_op(op!(
+            eq_contained_value,
+            (&gov_id, "country"),
+            &dict,
+            "alice"
+        ))?;
+        assert_eq!(
+            st,
+            Statement(
+                NativeStatement::EqualsContainedValue,
+                vec![
+                    StatementArg::Key(AnchoredKey(gov_id.origin(), "country".into())),
+                    dict.1[0].clone(),
+                    StatementArg::Literal(Value::from("alice")),
+                ]
+            )
+        );
+        assert!(builder.prove(&mut prover)?.verify());
+
+        // bob's country isn't US
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&gov_id);
+        let dict = builder.op(false, op!(new_entry, ("citizens", citizens.clone())))?;
+        builder.pub_op(op!(eq_contained_value, (&gov_id, "country"), &dict, "bob"))?;
+        assert!(!builder.prove(&mut prover)?.verify());
+
+        // carol isn't in the dictionary, so there's no proof to build
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&gov_id);
+        let dict = builder.op(false, op!(new_entry, ("citizens", citizens)))?;
+        assert!(builder
+            .pub_op(op!(
+                eq_contained_value,
+                (&gov_id, "country"),
+                &dict,
+                "carol"
+            ))
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_rename_contained_by() -> Result<()> {
+        let params = Params::default();
+        let issuers = Value::Dictionary(Dictionary::new(&HashMap::new()));
+        let mut builder = SignedPodBuilder::new(&params);
+        builder.insert("issuers", issuers.clone());
+        builder.insert("issuer", "Giggles");
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let pod = builder.sign(&mut signer)?;
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&pod);
+        // Contains(self.c0, pod.issuer), where self.c0 holds the issuers dictionary
+        let contains = builder.op(false, op!(contains, &issuers, (&pod, "issuer")))?;
+        let issuers_entry = builder.statements[0].clone();
+        // Equal(self.c0, pod.issuers)
+        let eq = builder.op(false, op!(eq, &issuers_entry, (&pod, "issuers")))?;
+        let st = builder.pub_op(op!(rename, contains.clone(), eq))?;
+        assert_eq!(
+            st,
+            Statement(
+                NativeStatement::Contains,
+                vec![
+                    StatementArg::Key(AnchoredKey(pod.origin(), "issuers".into())),
+                    StatementArg::Key(AnchoredKey(pod.origin(), "issuer".into())),
+                ]
+            )
+        );
+        // Equal(pod.issuers, self.c0), as in the key-sorted public statements of an input pod
+        let eq_reversed = builder.op(false, op!(eq, (&pod, "issuers"), &issuers_entry))?;
+        assert_eq!(builder.op(false, op!(rename, contains, eq_reversed))?, st);
+
+        let mut prover = MockProver::default();
+        let main_pod = builder.prove(&mut prover)?;
+        assert!(main_pod.pod.verify());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_transitive_equal_chain() -> Result<()> {
+        let params = Params::default();
+        let mut builder = MainPodBuilder::new(&params);
+        let entries = (0..5)
+            .map(|i| {
+                builder.op(
+                    false,
+                    Operation(
+                        NativeOperation::NewEntry,
+                        vec![OperationArg::Entry(format!("k{}", i), Value::from(7))],
+                    ),
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let eqs = entries
+            .windows(2)
+            .map(|w| builder.op(false, op!(eq, &w[0], &w[1])))
+            .collect::<Result<Vec<_>>>()?;
+
+        let chain = eqs.iter().map(OperationArg::from).collect_vec();
+        let st = builder.pub_op(Operation(NativeOperation::TransitiveEqualFromChain, chain))?;
+        assert_eq!(
+            st,
+            Statement(
+                NativeStatement::Equal,
+                vec![eqs[0].1[0].clone(), eqs[3].1[1].clone()]
+            )
+        );
+
+        // A link with its keys reversed, as in the key-sorted public statements of an input pod
+        let reversed = Statement(
+            NativeStatement::Equal,
+            vec![eqs[1].1[1].clone(), eqs[1].1[0].clone()],
+        );
+        let chain = [&eqs[0], &reversed, &eqs[2]]
+            .map(OperationArg::from)
+            .to_vec();
+        assert_eq!(
+            builder.op_transitive_equal_chain(&chain)?,
+            Statement(
+                NativeStatement::Equal,
+                vec![eqs[0].1[0].clone(), eqs[2].1[1].clone()]
+            )
+        );
+
+        // Broken chain
+        let broken = [&eqs[0], &eqs[2]].map(OperationArg::from).to_vec();
+        assert!(builder.op_transitive_equal_chain(&broken).is_err());
+        // Chain longer than max_operation_args
+        let too_long = [&eqs[0], &eqs[1], &eqs[2], &eqs[3], &eqs[3], &eqs[3]]
+            .map(OperationArg::from)
+            .to_vec();
+        assert!(builder.op_transitive_equal_chain(&too_long).is_err());
+
+        let mut prover = MockProver::default();
+        let main_pod = builder.prove(&mut prover)?;
+        assert!(main_pod.pod.verify());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_div_mod_of() -> Result<()> {
+        let params = Params::default();
+        let mut builder = SignedPodBuilder::new(&params);
+        builder.insert("total", 17);
+        builder.insert("parts", 5);
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let pod = builder.sign(&mut signer)?;
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&pod);
+        let st = builder.pub_op(op!(div, 3, (&pod, "total"), (&pod, "parts")))?;
+        assert_eq!(st.0, NativeStatement::DivOf);
+        let st = builder.pub_op(op!(mod, 2, (&pod, "total"), (&pod, "parts")))?;
+        assert_eq!(st.0, NativeStatement::ModOf);
+
+        let mut prover = MockProver::default();
+        let main_pod = builder.prove(&mut prover)?;
+        assert!(main_pod.pod.verify());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_and_or_not_of() -> Result<()> {
+        let params = Params::default();
+        let builder = tickets_sign_pod_builder(&params);
+        let mut signer = MockSigner { pk: "test".into() };
+        let ticket = builder.sign(&mut signer)?;
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&ticket);
+        // isConsumed = true, isRevoked = false
+        let st = builder.pub_op(op!(
+            and,
+            false,
+            (&ticket, "isConsumed"),
+            (&ticket, "isRevoked")
+        ))?;
+        assert_eq!(st.0, NativeStatement::AndOf);
+        let st = builder.pub_op(op!(
+            or,
+            true,
+            (&ticket, "isConsumed"),
+            (&ticket, "isRevoked")
+        ))?;
+        assert_eq!(st.0, NativeStatement::OrOf);
+        let st = builder.pub_op(op!(not, true, (&ticket, "isRevoked")))?;
+        assert_eq!(st.0, NativeStatement::NotOf);
+
+        let mut prover = MockProver::default();
+        let main_pod = builder.prove(&mut prover)?;
+        assert!(main_pod.pod.verify());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_sum_product_max_of() -> Result<()> {
+        let params = Params::default();
+        let mut builder = MainPodBuilder::new(&params);
+        let self_key =
+            |k: &str| StatementArg::Key(AnchoredKey(Origin(PodClass::Main, SELF), k.into()));
+
+        let st = builder.pub_op(Operation(NativeOperation::SumOf, op_args!(5, 2, 3)))?;
+        assert_eq!(st.0, NativeStatement::SumOf);
+        assert_eq!(st.1, vec![self_key("c0"), self_key("c1"), self_key("c2")]);
+        let st = builder.pub_op(Operation(NativeOperation::ProductOf, op_args!(6, 2, 3)))?;
+        assert_eq!(st.0, NativeStatement::ProductOf);
+        // Constants 2 and 3 are reused
+        assert_eq!(st.1, vec![self_key("c3"), self_key("c1"), self_key("c2")]);
+        let st = builder.pub_op(Operation(NativeOperation::MaxOf, op_args!(3, 2, 3)))?;
+        assert_eq!(st.0, NativeStatement::MaxOf);
+        assert_eq!(st.1, vec![self_key("c2"), self_key("c1"), self_key("c2")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_gt_lt_to_not_equal() -> Result<()> {
+        let params = Params::default();
+        let mut builder = MainPodBuilder::new(&params);
+        let gt = builder.op(false, op!(gt, 5, 3))?;
+        let lt = builder.op(false, op!(lt, 3, 5))?;
+
+        let ne = builder.pub_op(Operation(NativeOperation::GtToNotEqual, op_args!(&gt)))?;
+        assert_eq!(ne, Statement(NativeStatement::NotEqual, gt.1.clone()));
+        let ne = builder.pub_op(Operation(NativeOperation::LtToNotEqual, op_args!(&lt)))?;
+        assert_eq!(ne, Statement(NativeStatement::NotEqual, lt.1.clone()));
+
+        // A statement of the wrong kind is rejected
+        assert!(MainPodBuilder::op_to_not_equal(NativeStatement::Gt, &op_args!(&lt)).is_err());
+        assert!(MainPodBuilder::op_to_not_equal(NativeStatement::Lt, &op_args!(&gt)).is_err());
+
+        let mut prover = MockProver::default();
+        let main_pod = builder.prove(&mut prover)?;
+        assert!(main_pod.pod.verify());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_transitive_equal() -> Result<()> {
+        let params = Params::default();
+        let mut builder = MainPodBuilder::new(&params);
+        let new_entry = |k: &str| {
+            Operation(
+                NativeOperation::NewEntry,
+                vec![OperationArg::Entry(k.into(), Value::Int(7))],
+            )
+        };
+        let a = builder.op(false, new_entry("a"))?;
+        let b = builder.op(false, new_entry("b"))?;
+        let c = builder.op(false, new_entry("c"))?;
+        let ab = builder.op(false, op!(eq, &a, &b))?;
+        let bc = builder.op(false, op!(eq, &b, &c))?;
+        let ac = builder.pub_op(Operation(
+            NativeOperation::TransitiveEqualFromStatements,
+            op_args!(&ab, &bc),
+        ))?;
+        assert_eq!(
+            ac,
+            Statement(
+                NativeStatement::Equal,
+                vec![ab.1[0].clone(), bc.1[1].clone()]
+            )
+        );
+
+        // The first equality reversed, as in the key-sorted public statements of an input pod
+        let ba = builder.op(false, op!(eq, &b, &a))?;
+        let ac_reversed = builder.op(
+            false,
+            Operation(
+                NativeOperation::TransitiveEqualFromStatements,
+                op_args!(&ba, &bc),
+            ),
+        )?;
+        assert_eq!(ac_reversed, ac);
+
+        // Mismatched middle key
+        let d = StatementArg::Key(AnchoredKey(Origin(PodClass::Main, SELF), "d".into()));
+        let cd = Statement(NativeStatement::Equal, vec![bc.1[1].clone(), d]);
+        assert!(MainPodBuilder::op_transitive_equal(&op_args!(&ab, &cd)).is_err());
+        // Not an equality
+        let gt = Statement(NativeStatement::Gt, ab.1.clone());
+        assert!(MainPodBuilder::op_transitive_equal(&op_args!(&ab, &gt)).is_err());
+
+        let mut prover = MockProver::default();
+        let main_pod = builder.prove(&mut prover)?;
+        assert!(main_pod.pod.verify());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_op_invalid_arguments() -> Result<()> {
+        let params = Params::default();
+        let mut builder = MainPodBuilder::new(&params);
+        let gt = builder.op(false, op!(gt, 5, 3))?;
+        let n_statements = builder.statements.len();
+
+        // Entry-based operations need ValueOf statements
+        assert!(builder.pub_op(op!(eq, &gt, 5)).is_err());
+        // EqualToLiteral needs an entry followed by a literal
+        assert!(builder.pub_op(op!(eq_literal, 5, &gt)).is_err());
+        // GtToNotEqual needs a Gt statement
+        assert!(builder.pub_op(op!(lt_to_ne, &gt)).is_err());
+        // A new literal before the invalid argument
+        assert!(builder.pub_op(op!(eq, 7, &gt)).is_err());
+        // Failed operations don't leave statements behind
+        assert_eq!(builder.statements.len(), n_statements);
+        assert_eq!(builder.operations.len(), n_statements);
+        assert!(builder.public_statements.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_op_type_checking() -> Result<()> {
+        let params = Params::default();
+        let mut builder = SignedPodBuilder::new(&params);
+        builder.insert("first", "Alice");
+        builder.insert("last", "Smith");
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let pod = builder.sign(&mut signer)?;
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&pod);
+        // Arithmetic over string entries
+        assert!(builder
+            .pub_op(op!(sum, (&pod, "first"), (&pod, "first"), (&pod, "last")))
+            .is_err());
+        assert!(builder.pub_op(op!(product, 6, 2, "three")).is_err());
+        // Boolean operations over non-boolean values
+        assert!(builder.pub_op(op!(not, true, 2)).is_err());
+        // Membership in something that isn't a container
+        assert!(builder
+            .pub_op(op!(contains, "Alice", (&pod, "first")))
+            .is_err());
+        // Nothing was added by the rejected operations
+        assert!(builder.statements.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_contains_from_container_entry() -> Result<()> {
+        let params = Params::default();
+        let issuers = Value::Dictionary(Dictionary::new(&HashMap::from([(
+            hash_str("Giggles"),
+            middleware::Value::from(1),
+        )])));
+        let mut builder = SignedPodBuilder::new(&params);
+        builder.insert("issuers", issuers.clone());
+        builder.insert("issuer", "Giggles");
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let pod = builder.sign(&mut signer)?;
+
+        // The entry holding the dictionary keeps its type, unlike the scalar one
+        let value_of = |key: &str| match OperationArg::from((&pod, key)) {
+            OperationArg::Statement(Statement(NativeStatement::ValueOf, args)) => args[1].clone(),
+            arg => panic!("unexpected argument {}", arg),
+        };
+        assert_eq!(value_of("issuers"), StatementArg::Literal(issuers));
+        assert!(matches!(
+            value_of("issuer"),
+            StatementArg::Literal(Value::Raw(_))
+        ));
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&pod);
+        let expected = Statement(
+            NativeStatement::Contains,
+            vec![
+                StatementArg::Key(AnchoredKey(pod.origin(), "issuers".into())),
+                StatementArg::Key(AnchoredKey(pod.origin(), "issuer".into())),
+            ],
+        );
+        let st = builder.pub_op(op!(contains, (&pod, "issuers"), (&pod, "issuer")))?;
+        assert_eq!(st, expected);
+        let main_pod = builder.prove(&mut MockProver::default())?;
+        assert!(main_pod.pod.verify());
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&pod);
+        // A scalar entry can't be the container
+        assert!(builder
+            .contains(true, (&pod, "issuer"), (&pod, "issuers"))
+            .is_err());
+        let st = builder.contains(true, (&pod, "issuers"), (&pod, "issuer"))?;
+        assert_eq!(st, expected);
+        let main_pod = builder.prove(&mut MockProver::default())?;
+        assert!(main_pod.pod.verify());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_prove_too_many_statements() -> Result<()> {
+        let params = Params::builder()
+            .max_statements(4)
+            .max_public_statements(2)
+            .build();
+        let mut prover = MockProver::default();
+
+        // Below max_statements, but above the 2 private slots left by the public ones
+        let mut builder = MainPodBuilder::new(&params);
+        for i in 0..3i64 {
+            builder.op(
+                false,
+                Operation(
+                    NativeOperation::NewEntry,
+                    vec![OperationArg::Entry(format!("k{}", i), Value::from(i))],
+                ),
+            )?;
+        }
+        let err = builder.prove(&mut prover).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<BuilderError>(),
+            Some(&BuilderError::TooManyStatements { count: 3, max: 2 })
+        );
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.pub_op(op!(eq, 1, 1))?;
+        let err = builder.prove(&mut prover).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<BuilderError>(),
+            Some(&BuilderError::TooManyPublicStatements { count: 2, max: 1 })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_main_pod_verify() -> Result<()> {
+        let params = Params::default();
+        let (gov_id, pay_stub) = zu_kyc_sign_pod_builders(&params);
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let gov_id = gov_id.sign(&mut signer)?;
+        let mut signer = MockSigner {
+            pk: "ZooDeel".into(),
+        };
+        let pay_stub = pay_stub.sign(&mut signer)?;
+        let kyc = zu_kyc_pod_builder(&params, &gov_id, &pay_stub)?;
+
+        let mut prover = MockProver::default();
+        let kyc_pod = kyc.prove(&mut prover)?;
+        assert!(kyc_pod.verify());
+
+        let pub_statements = kyc_pod.pub_statements();
+        // The pod type statement comes first, then the statements made public by the builder.
+        assert_eq!(pub_statements.len(), 1 + kyc.public_statements.len());
+        assert_eq!(pub_statements[0].0, NativeStatement::ValueOf);
+        assert_eq!(
+            pub_statements[0].1[0],
+            StatementArg::Key(AnchoredKey(kyc_pod.origin(), KEY_TYPE.into()))
+        );
+        let lt = pub_statements
+            .iter()
+            .find(|st| st.0 == NativeStatement::Lt)
+            .unwrap();
+        assert_eq!(
+            lt.1[0],
+            StatementArg::Key(AnchoredKey(gov_id.origin(), "dateOfBirth".into()))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_signed_pod_reserved_keys() -> Result<()> {
+        let params = Params::default();
+        let mut builder = SignedPodBuilder::new(&params);
+        builder.insert("idNumber", "4242424242");
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let pod = builder.sign(&mut signer)?;
+        let kvs = pod.kvs();
+        assert_eq!(
+            kvs.get(&hash_str(KEY_TYPE)),
+            Some(&middleware::Value::from(PodType::MockSigned))
+        );
+        assert_eq!(
+            kvs.get(&hash_str(KEY_SIGNER)),
+            Some(&middleware::Value(hash_str("ZooGov").0))
+        );
+        assert_eq!(
+            pod.key_string_map.get(&hash_str(KEY_SIGNER)).unwrap(),
+            KEY_SIGNER
+        );
+
+        // Reserved keys can't be set manually
+        builder.insert(KEY_TYPE, PodType::Signed as i64);
+        assert!(builder.sign(&mut signer).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_signed_pod_builder_remove() -> Result<()> {
+        let params = Params::default();
+        let mut builder = SignedPodBuilder::new(&params);
+        builder.insert("idNumber", "4242424242");
+        builder.insert("typo", 42);
+        assert!(builder.contains_key("typo"));
+        assert_eq!(builder.remove("typo"), Some(Value::Int(42)));
+        assert!(!builder.contains_key("typo"));
+        assert_eq!(builder.remove("typo"), None);
+
+        // Reserved keys can't be removed
+        for key in [KEY_TYPE, KEY_SIGNER, KEY_SIGNERS] {
+            builder.insert(key, "ZooGov");
+            assert_eq!(builder.remove(key), None);
+            assert!(builder.contains_key(key));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_compile_literal_arg() -> Result<()> {
+        let params = Params::default();
+        let mut builder = MainPodBuilder::new(&params);
+        // Bypass `op`, which would replace the literals by ValueOf statements
+        let self_key =
+            |k: &str| StatementArg::Key(AnchoredKey(Origin(PodClass::Main, SELF), k.into()));
+        builder.insert((
+            Statement(NativeStatement::Equal, vec![self_key("a"), self_key("b")]),
+            Operation(NativeOperation::EqualFromEntries, op_args!(1, 1)),
+        ));
+
+        let mut prover = MockProver::default();
+        assert!(builder.prove(&mut prover).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_reuse_constants() -> Result<()> {
+        let params = Params::default();
+        let mut builder = SignedPodBuilder::new(&params);
+        builder.insert("a", 1);
+        builder.insert("b", 2);
+        builder.insert("c", 3);
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let pod = builder.sign(&mut signer)?;
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&pod);
+        for key in ["a", "b", "c"] {
+            builder.pub_op(op!(lt, 0, (&pod, key)))?;
+        }
+        let new_entries = builder
+            .operations
+            .iter()
+            .filter(|op| op.0 == NativeOperation::NewEntry)
+            .count();
+        assert_eq!(new_entries, 1);
+
+        // A private constant isn't reused by a public operation, and values of different types
+        // with the same lowering get different constants
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&pod);
+        builder.op(false, op!(lt, 0, (&pod, "a")))?;
+        let st = builder.pub_op(op!(lt, 0, (&pod, "b")))?;
+        builder.op(false, op!(eq, true, true))?;
+        builder.op(false, op!(lt, 0, 1))?;
+        // A public constant is reused by a private operation
+        builder.pub_op(op!(gt, 5, (&pod, "c")))?;
+        builder.op(false, op!(gt, 5, (&pod, "a")))?;
+        let new_entries = builder
+            .statements
+            .iter()
+            .filter(|st| st.0 == NativeStatement::ValueOf)
+            .collect::<Vec<_>>();
+        assert_eq!(new_entries.len(), 5);
+        assert!(builder.public_statements.contains(new_entries[1]));
+        assert_eq!(st.1[0], new_entries[1].1[0]);
+
+        let mut prover = MockProver::default();
+        let main_pod = builder.prove(&mut prover)?;
+        assert!(main_pod.verify());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_main_pod_value_arg() -> Result<()> {
+        let params = Params::default();
+        let mut prover = MockProver::default();
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.pub_op(op!(new_entry, ("score", 42)))?;
+        let main_pod = builder.prove(&mut prover)?;
+
+        let score = OperationArg::try_from((&main_pod, "score"))?;
+        assert!(OperationArg::try_from((&main_pod, "missing")).is_err());
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_main_pod(main_pod);
+        builder.pub_op(op!(gt, score, 10))?;
+        let main_pod = builder.prove(&mut prover)?;
+        assert!(main_pod.verify());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_reveal() -> Result<()> {
+        let params = Params::default();
+        let mut builder = MainPodBuilder::new(&params);
+        let st = builder.op(false, op!(lt, 1, 2))?;
+        builder.reveal(&st)?;
+        assert_eq!(builder.public_statements, vec![st.clone()]);
+
+        // A statement that was never derived
+        let fabricated = Statement(NativeStatement::Gt, st.1.clone());
+        assert!(builder.reveal(&fabricated).is_err());
+        assert_eq!(builder.public_statements.len(), 1);
+
+        let mut prover = MockProver::default();
+        let main_pod = builder.prove(&mut prover)?;
+        assert!(main_pod.verify());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_public_statements_subset() -> Result<()> {
+        let params = Params::default();
+        let mut prover = MockProver::default();
+
+        let mut builder = MainPodBuilder::new(&params);
+        let st = builder.pub_op(op!(lt, 1, 2))?;
+        assert!(builder.prove(&mut prover)?.verify());
+
+        // Inconsistent builder state: a public statement that was never derived
+        let fabricated = Statement(NativeStatement::Gt, st.1.clone());
+        builder.public_statements.push(fabricated.clone());
+        let err = builder.prove(&mut prover).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<BuilderError>(),
+            Some(&BuilderError::PublicStatementNotDerived(fabricated))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_foreign_new_entry() -> Result<()> {
+        let params = Params::default();
+        let (gov_id, _) = zu_kyc_sign_pod_builders(&params);
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let gov_id = gov_id.sign(&mut signer)?;
+        let mut prover = MockProver::default();
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&gov_id);
+        // A new entry claiming to come from the signed pod
+        let forged = Statement(
+            NativeStatement::ValueOf,
+            vec![
+                StatementArg::Key(AnchoredKey(gov_id.origin(), "idNumber".into())),
+                StatementArg::Literal(Value::from("1111111111")),
+            ],
+        );
+        builder.insert((forged.clone(), Operation(NativeOperation::NewEntry, vec![])));
+        let err = builder.prove(&mut prover).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<BuilderError>(),
+            Some(&BuilderError::ForeignNewEntry(forged))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_copy_from() -> Result<()> {
+        let params = Params::default();
+        let mut prover = MockProver::default();
+
+        let mut builder = MainPodBuilder::new(&params);
+        let a = builder.op(false, op!(new_entry, ("a", 9)))?;
+        let b = builder.op(false, op!(new_entry, ("b", 3)))?;
+        builder.pub_op(op!(gt, &a, &b))?;
+        let child = builder.prove(&mut prover)?;
+        let child_id = child.id();
+
+        let mut builder = MainPodBuilder::new(&params);
+        let gt = builder
+            .add_main_pod(child)
+            .into_iter()
+            .find(|st| st.0 == NativeStatement::Gt)
+            .unwrap();
+        assert_eq!(builder.copy_from(true, child_id, &gt)?, gt);
+
+        let fabricated = Statement(NativeStatement::Lt, gt.1.clone());
+        assert!(builder.copy_from(true, child_id, &fabricated).is_err());
+        assert!(builder
+            .copy_from(true, PodId(hash_str("other")), &gt)
+            .is_err());
+
+        assert!(builder.prove(&mut prover)?.verify());
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_signed_pod_get() -> Result<()> {
+        let params = Params::default();
+        let (gov_id, _) = zu_kyc_sign_pod_builders(&params);
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let gov_id = gov_id.sign(&mut signer)?;
+
+        assert_eq!(
+            gov_id.get("idNumber"),
+            Some(Value::String("4242424242".into()))
+        );
+        assert_eq!(gov_id.get("dateOfBirth"), Some(Value::Int(1169909384)));
+        assert_eq!(
+            gov_id.get(KEY_TYPE),
+            Some(Value::Raw(PodType::MockSigned.into()))
+        );
+        assert_eq!(gov_id.get("missing"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_signed_pod_signer() -> Result<()> {
+        let params = Params::default();
+        let (gov_id, _) = zu_kyc_sign_pod_builders(&params);
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let gov_id = gov_id.sign(&mut signer)?;
+
+        assert_eq!(gov_id.signer(), Some(Value::Raw(signer.pk_value())));
+        assert_eq!(gov_id.pod.signer(), Some(signer.pk_value()));
+        assert_eq!(gov_id.signer(), Some(Value::Raw(hash_str("ZooGov").into())));
+
+        // Main pods don't have a signer
+        let mut builder = MainPodBuilder::new(&params);
+        builder.pub_op(op!(new_entry, ("a", 1)))?;
+        let main_pod = builder.prove(&mut MockProver::default())?;
+        assert_eq!(main_pod.pod.signer(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_set_cardinality() -> Result<()> {
+        let params = Params::default();
+        let credentials = Set::new(&(0i64..5).map(middleware::Value::from).collect());
+        // The signer checks the cardinality proof before attesting the count
+        let proof = credentials.prove_cardinality()?;
+        let len = Set::verify_cardinality(credentials.commitment(), &proof)?;
+        let mut builder = SignedPodBuilder::new(&params);
+        builder.insert("credentials", Value::Set(credentials));
+        builder.insert("num_credentials", len as i64);
+        let pod = builder.sign(&mut MockSigner {
+            pk: "issuer".into(),
+        })?;
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&pod);
+        builder.pub_op(op!(gteq, (&pod, "num_credentials"), 3))?;
+        let main_pod = builder.prove(&mut MockProver::default())?;
+        assert!(main_pod.pod.verify());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_signed_pod_serde() -> Result<()> {
+        let params = Params::default();
+        let (gov_id, _) = zu_kyc_sign_pod_builders(&params);
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let gov_id = gov_id.sign(&mut signer)?;
+
+        let json = serde_json::to_string(&gov_id)?;
+        let loaded: SignedPod = serde_json::from_str(&json)?;
+        assert!(loaded.verify());
+        assert_eq!(loaded.id(), gov_id.id());
+        assert_eq!(loaded.kvs(), gov_id.kvs());
+        assert_eq!(loaded.key_string_map, gov_id.key_string_map);
+        assert_eq!(
+            loaded.key_string_map.get(&hash_str("idNumber")),
+            Some(&"idNumber".to_string())
+        );
+        assert_eq!(
+            loaded.get("dateOfBirth"),
+            Some(Value::Raw((&Value::Int(1169909384)).into()))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_main_pod_to_json() -> Result<()> {
+        let params = Params::default();
+        let (gov_id, pay_stub) = zu_kyc_sign_pod_builders(&params);
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let gov_id = gov_id.sign(&mut signer)?;
+        let pay_stub = pay_stub.sign(&mut signer)?;
+        let kyc = zu_kyc_pod_builder(&params, &gov_id, &pay_stub)?;
+        let kyc = kyc.prove(&mut MockProver::default())?;
+
+        let json = kyc.to_json();
+        assert_eq!(json["id"], serde_json::to_value(kyc.id())?);
+        assert_eq!(
+            json["type"],
+            serde_json::to_value(middleware::Value(hash_str(VALUE_TYPE).0))?
+        );
+        let input_ids: Vec<_> = json["input_pods"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|pod| pod["id"].clone())
+            .collect();
+        assert!(input_ids.contains(&serde_json::to_value(gov_id.id())?));
+        assert!(input_ids.contains(&serde_json::to_value(pay_stub.id())?));
+
+        let statements = json["public_statements"].as_array().unwrap();
+        assert_eq!(statements.len(), 4);
+        assert_eq!(statements[1]["predicate"], "Lt");
+        assert_eq!(statements[1]["args"][0]["key"], "dateOfBirth");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_signed_pod_from_json() -> Result<()> {
+        let params = Params::default();
+        let (gov_id, _) = zu_kyc_sign_pod_builders(&params);
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let gov_id = gov_id.sign(&mut signer)?;
+        let json = gov_id.to_json()?;
+
+        let loaded = SignedPod::from_json(json.clone())?;
+        assert_eq!(loaded.id(), gov_id.id());
+        assert_eq!(loaded.kvs(), gov_id.kvs());
+
+        // Change the value of one entry, keeping the id and signature
+        let mut tampered = json;
+        let value = &mut tampered["pod"]["MockSigned"]["kvs"][0][1][0];
+        *value = (value.as_u64().unwrap() + 1).into();
+        assert!(SignedPod::from_json(tampered).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_statement_parse() -> Result<()> {
+        let params = Params::default();
+        let (gov_id, pay_stub) = zu_kyc_sign_pod_builders(&params);
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let gov_id = gov_id.sign(&mut signer)?;
+        let pay_stub = pay_stub.sign(&mut signer)?;
+        let origins = [gov_id.origin(), pay_stub.origin()];
+
+        let mut builder = MainPodBuilder::new(&params);
+        builder.add_signed_pod(&gov_id);
+        builder.add_signed_pod(&pay_stub);
+        let eq = builder.op(
+            false,
+            op!(
+                eq,
+                (gov_id, "socialSecurityNumber"),
+                (pay_stub, "socialSecurityNumber")
+            ),
+        )?;
+        let gt = builder.op(false, op!(gt, (gov_id, "dateOfBirth"), 1000))?;
+        let value_of = builder.op(false, op!(new_entry, ("name", "Molly Doe")))?;
+
+        for st in [eq.clone(), gt, value_of] {
+            assert_eq!(Statement::parse(&format!("{}", st), &origins)?, st);
+        }
+        // Keys of unknown pods can't be resolved
+        assert!(Statement::parse(&format!("{}", eq), &origins[..1]).is_err());
+        assert!(Statement::parse("Foo self.a", &origins).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_value_parse() -> Result<()> {
+        assert_eq!(
+            Value::parse("\"Molly Doe\"")?,
+            Value::String("Molly Doe".into())
+        );
+        assert_eq!(Value::parse("\"\"")?, Value::String("".into()));
+        assert_eq!(Value::parse("true")?, Value::Bool(true));
+        assert_eq!(Value::parse("false")?, Value::Bool(false));
+        assert_eq!(Value::parse("1169909384")?, Value::Int(1169909384));
+        assert_eq!(Value::parse("-42")?, Value::Int(-42));
+        let hash = hash_str("foo");
+        assert_eq!(
+            Value::parse(&format!("0x{:#}", hash))?,
+            Value::Raw(hash.into())
+        );
+
+        // Unquoted words are ambiguous
+        assert!(Value::parse("Molly").is_err());
+        assert!(Value::parse("0x1234").is_err());
+        assert!(Value::parse("\"a\"b\"").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_serialization_insertion_order() -> Result<()> {
+        let params = Params::default();
+        let entries = [
+            ("idNumber", Value::from("4242424242")),
+            ("dateOfBirth", Value::from(1169909384)),
+            ("socialSecurityNumber", Value::from("G2121210")),
+            ("isAdult", Value::from(true)),
+        ];
+        let sign = |entries: &[(&str, Value)]| -> Result<SignedPod> {
+            let mut builder = SignedPodBuilder::new(&params);
+            for (k, v) in entries {
+                builder.insert(*k, v.clone());
+            }
+            builder.sign(&mut MockSigner {
+                pk: "ZooGov".into(),
+            })
+        };
+        let pod_a = sign(&entries)?;
+        let reversed: Vec<_> = entries.iter().rev().cloned().collect();
+        let pod_b = sign(&reversed)?;
+
+        assert_eq!(pod_a.id(), pod_b.id());
+        assert_eq!(pod_a.pod.pub_statements(), pod_b.pod.pub_statements());
+        assert_eq!(pod_a.pod.to_fields(), pod_b.pod.to_fields());
+        assert_eq!(serde_json::to_vec(&pod_a)?, serde_json::to_vec(&pod_b)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_signed_pod_iter() -> Result<()> {
+        let params = Params::default();
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let sign = |signer: &mut MockSigner| -> Result<SignedPod> {
+            let (gov_id, _) = zu_kyc_sign_pod_builders(&params);
+            gov_id.sign(signer)
+        };
+        let (pod_a, pod_b) = (sign(&mut signer)?, sign(&mut signer)?);
+
+        let keys: Vec<_> = pod_a.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, ["dateOfBirth", "idNumber", "socialSecurityNumber"]);
+        assert!(pod_a.iter().eq(pod_b.iter()));
+        assert_eq!(format!("{}", pod_a), format!("{}", pod_b));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_value_display() {
+        // Typed values are displayed according to their type, not guessed from the lowered value
+        assert_eq!(Value::String("A".into()).to_string(), "\"A\"");
+        assert_eq!(Value::String("5".into()).to_string(), "\"5\"");
+        assert_eq!(Value::Int(-5).to_string(), "-5");
+        assert_eq!(Value::Bool(true).to_string(), "true");
+        assert_eq!(Value::Raw(middleware::Value::from(5)).to_string(), "5");
+        let hash = hash_str("A");
+        assert_eq!(Value::Raw(hash.into()).to_string(), hash.to_string());
+        let set = Set::new(&vec![middleware::Value::from(1)]);
+        assert_eq!(
+            Value::Set(set.clone()).to_string(),
+            format!("set:{}", set.commitment())
+        );
+    }
+
+    #[test]
+    fn test_front_value_eq_middleware() {
+        assert_eq!(Value::Int(5), middleware::Value::from(5));
+        assert_eq!(middleware::Value::from(5), Value::Int(5));
+        assert_ne!(Value::Int(5), middleware::Value::from(6));
+
+        assert_eq!(Value::Bool(true), middleware::Value::from(1));
+        assert_eq!(middleware::Value::from(0), Value::Bool(false));
+        assert_ne!(Value::Bool(true), middleware::Value::from(0));
+
+        let s = Value::String("hello".into());
+        assert_eq!(s, middleware::Value(hash_str("hello").0));
+        assert_eq!(middleware::Value(hash_str("hello").0), s);
+        assert_ne!(s, middleware::Value(hash_str("bye").0));
+    }
+
+    #[test]
+    fn test_front_copy_from_main_pod() -> Result<()> {
+        let params = Params::default();
+        let mut prover = MockProver::default();
+
+        let mut builder = MainPodBuilder::new(&params);
+        let a = builder.op(false, op!(new_entry, ("a", 9)))?;
+        let b = builder.op(false, op!(new_entry, ("b", 3)))?;
+        builder.pub_op(op!(gt, &a, &b))?;
+        let child = builder.prove(&mut prover)?;
+        let child_origin = child.origin();
+
+        let mut builder = MainPodBuilder::new(&params);
+        let statements = builder.add_main_pod(child);
+        let gt = statements
+            .into_iter()
+            .find(|st| st.0 == NativeStatement::Gt)
+            .unwrap();
+        assert_eq!(
+            gt.1,
+            vec![
+                StatementArg::Key(AnchoredKey(child_origin.clone(), "a".into())),
+                StatementArg::Key(AnchoredKey(child_origin.clone(), "b".into())),
+            ]
+        );
+        let copy = builder.pub_op(op!(copy, &gt))?;
+        assert_eq!(copy, gt);
+        assert!(builder.op(false, op!(copy, &gt, &gt)).is_err());
+
+        let parent = builder.prove(&mut prover)?;
+        assert!(parent.verify());
+        assert!(parent.pub_statements().contains(&gt));
+        Ok(())
+    }
+
+    #[test]
+    fn test_front_referenced_keys() -> Result<()> {
+        let params = Params::default();
+        let (gov_id, pay_stub) = zu_kyc_sign_pod_builders(&params);
+        let mut signer = MockSigner {
+            pk: "ZooGov".into(),
+        };
+        let gov_id = gov_id.sign(&mut signer)?;
+        let mut signer = MockSigner {
+            pk: "ZooDeel".into(),
+        };
+        let pay_stub = pay_stub.sign(&mut signer)?;
+        let kyc =
+            zu_kyc_pod_builder(&params, &gov_id, &pay_stub)?.prove(&mut MockProver::default())?;
+
+        let keys = kyc.referenced_keys();
+        let ak = |pod: &SignedPod, key: &str| middleware::AnchoredKey(pod.id(), hash_str(key));
+        for expected in [
+            ak(&gov_id, "idNumber"),
+            ak(&gov_id, "dateOfBirth"),
+            ak(&gov_id, "socialSecurityNumber"),
+            ak(&pay_stub, "socialSecurityNumber"),
+            ak(&pay_stub, "startDate"),
+        ] {
+            assert!(keys.contains(&expected), "missing {:?}", expected);
+        }
+        assert!(keys.contains(&middleware::AnchoredKey(kyc.id(), hash_str(KEY_TYPE))));
+        // The only other keys are the constants of the kyc pod
+        assert!(keys
+            .iter()
+            .all(|ak| [gov_id.id(), pay_stub.id(), kyc.id()].contains(&ak.0)));
+        assert!(keys
+            .windows(2)
+            .all(|w| (w[0].0 .0, w[0].1) < (w[1].0 .0, w[1].1)));
+        Ok(())
+    }
 }
@@ -1,8 +1,9 @@
+use anyhow::{anyhow, Result};
 use std::fmt;
 
-use crate::middleware::{hash_str, NativeOperation, NativeStatement};
+use crate::middleware::{self, hash_str, NativeOperation, NativeStatement};
 
-use super::{AnchoredKey, SignedPod, Statement, StatementArg, Value};
+use super::{AnchoredKey, MainPod, SignedPod, Statement, StatementArg, Value};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum OperationArg {
@@ -51,20 +52,62 @@ impl From<bool> for OperationArg {
     }
 }
 
+impl From<Statement> for OperationArg {
+    fn from(s: Statement) -> Self {
+        Self::Statement(s)
+    }
+}
+
+impl From<&Statement> for OperationArg {
+    fn from(s: &Statement) -> Self {
+        Self::Statement(s.clone())
+    }
+}
+
 impl From<(&SignedPod, &str)> for OperationArg {
     fn from((pod, key): (&SignedPod, &str)) -> Self {
         // TODO: Actual value, TryFrom.
         let value = pod.kvs().get(&hash_str(key)).unwrap().clone();
+        // Containers keep their type, so that the statement can be used as a container operand
+        let value = match pod.get(key) {
+            Some(v) if v.is_container() => v,
+            _ => Value::Raw(value),
+        };
         Self::Statement(Statement(
             NativeStatement::ValueOf,
             vec![
                 StatementArg::Key(AnchoredKey(pod.origin(), key.to_string())),
-                StatementArg::Literal(Value::Raw(value)),
+                StatementArg::Literal(value),
             ],
         ))
     }
 }
 
+impl TryFrom<(&MainPod, &str)> for OperationArg {
+    type Error = anyhow::Error;
+    fn try_from((pod, key): (&MainPod, &str)) -> Result<Self> {
+        let value = pod
+            .pod
+            .kvs()
+            .get(&middleware::AnchoredKey(pod.id(), hash_str(key)))
+            .cloned()
+            .ok_or_else(|| {
+                anyhow!(
+                    "Key {} not found in the public entries of {}.",
+                    key,
+                    pod.id()
+                )
+            })?;
+        Ok(Self::Statement(Statement(
+            NativeStatement::ValueOf,
+            vec![
+                StatementArg::Key(AnchoredKey(pod.origin(), key.to_string())),
+                StatementArg::Literal(Value::Raw(value)),
+            ],
+        )))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Operation(pub NativeOperation, pub Vec<OperationArg>);
 
@@ -2,9 +2,9 @@ use std::fmt;
 
 use anyhow::{anyhow, Result};
 
-use crate::middleware::{self, NativeStatement};
+use crate::middleware::{self, NativeStatement, SELF};
 
-use super::{AnchoredKey, Value};
+use super::{AnchoredKey, Origin, PodClass, Value};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum StatementArg {
@@ -52,12 +52,49 @@ impl TryFrom<Statement> for middleware::Statement {
             (NS::Lt, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), None)) => {
                 MS::Lt(ak1.into(), ak2.into())
             }
+            (NS::Gteq, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), None)) => {
+                MS::Gteq(ak1.into(), ak2.into())
+            }
+            (NS::Lteq, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), None)) => {
+                MS::Lteq(ak1.into(), ak2.into())
+            }
             (NS::Contains, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), None)) => {
                 MS::Contains(ak1.into(), ak2.into())
             }
             (NS::NotContains, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), None)) => {
                 MS::NotContains(ak1.into(), ak2.into())
             }
+            (NS::EqualLiteral, (Some(SA::Key(ak)), Some(SA::Literal(v)), None)) => {
+                MS::EqualLiteral(ak.into(), (&v).into())
+            }
+            (NS::GtLiteral, (Some(SA::Key(ak)), Some(SA::Literal(v)), None)) => {
+                MS::GtLiteral(ak.into(), (&v).into())
+            }
+            (NS::LtLiteral, (Some(SA::Key(ak)), Some(SA::Literal(v)), None)) => {
+                MS::LtLiteral(ak.into(), (&v).into())
+            }
+            (NS::DivOf, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), Some(SA::Key(ak3)))) => {
+                MS::DivOf(ak1.into(), ak2.into(), ak3.into())
+            }
+            (NS::ModOf, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), Some(SA::Key(ak3)))) => {
+                MS::ModOf(ak1.into(), ak2.into(), ak3.into())
+            }
+            (NS::AndOf, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), Some(SA::Key(ak3)))) => {
+                MS::AndOf(ak1.into(), ak2.into(), ak3.into())
+            }
+            (NS::OrOf, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), Some(SA::Key(ak3)))) => {
+                MS::OrOf(ak1.into(), ak2.into(), ak3.into())
+            }
+            (NS::NotOf, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), None)) => {
+                MS::NotOf(ak1.into(), ak2.into())
+            }
+            (NS::InRange, (Some(SA::Key(ak)), Some(SA::Literal(min)), Some(SA::Literal(max)))) => {
+                MS::InRange(ak.into(), (&min).into(), (&max).into())
+            }
+            (
+                NS::EqualsContainedValue,
+                (Some(SA::Key(ak1)), Some(SA::Key(ak2)), Some(SA::Literal(key))),
+            ) => MS::EqualsContainedValue(ak1.into(), ak2.into(), (&key).into()),
             (NS::SumOf, (Some(SA::Key(ak1)), Some(SA::Key(ak2)), Some(SA::Key(ak3)))) => {
                 MS::SumOf(ak1.into(), ak2.into(), ak3.into())
             }
@@ -72,6 +109,67 @@ impl TryFrom<Statement> for middleware::Statement {
     }
 }
 
+impl Statement {
+    /// Parse a statement in the format of its `Display`.  Pod ids are displayed truncated, so
+    /// each anchored key is resolved against the displayed id of one of `origins` (a `self` key
+    /// always resolves to the pod being built).  Literals are parsed with [Value::parse].
+    pub fn parse(s: &str, origins: &[Origin]) -> Result<Self> {
+        let mut tokens = tokenize(s)?.into_iter();
+        let predicate = tokens.next().ok_or_else(|| anyhow!("Empty statement."))?;
+        let predicate = predicate
+            .parse::<NativeStatement>()
+            .map_err(|_| anyhow!("Unknown statement {}.", predicate))?;
+        let self_origin = Origin(PodClass::Main, SELF);
+        let args = tokens
+            .map(|token| {
+                let origin = token.split_once('.').and_then(|(pod_id, key)| {
+                    origins
+                        .iter()
+                        .chain(std::iter::once(&self_origin))
+                        .find(|origin| format!("{}", origin.1) == pod_id)
+                        .map(|origin| (origin, key))
+                });
+                match origin {
+                    Some((origin, key)) => Ok(StatementArg::Key(AnchoredKey(
+                        origin.clone(),
+                        key.to_string(),
+                    ))),
+                    None => Value::parse(&token).map(StatementArg::Literal),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Statement(predicate, args))
+    }
+}
+
+/// Split at whitespace, keeping quoted strings (with their quotes) as single tokens.
+fn tokenize(s: &str) -> Result<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut chars = s.trim().chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        let mut token = c.to_string();
+        if c == '"' {
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => token.push(c),
+                    None => return Err(anyhow!("Unterminated string in {}.", s)),
+                }
+            }
+            token.push('"');
+        } else {
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                token.push(c);
+            }
+        }
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
 impl fmt::Display for Statement {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:?} ", self.0)?;
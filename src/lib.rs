@@ -1,4 +1,13 @@
+/// Emit a `tracing` event when the `tracing` feature is enabled, and nothing otherwise.
+macro_rules! trace_event {
+    ($level:ident, $($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)+);
+    };
+}
+
 pub mod backends;
+pub mod error;
 pub mod frontend;
 pub mod middleware;
 pub mod primitives;
@@ -1,11 +1,11 @@
 /// This file implements the types defined at
 /// https://0xparc.github.io/pod2/values.html#dictionary-array-set .
-use anyhow::Result;
 use plonky2::hash::poseidon::PoseidonHash;
 use plonky2::plonk::config::Hasher;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use super::{Hash, Value, EMPTY};
+use crate::error::{Pod2Error, Result};
 use crate::primitives::merkletree::{MerkleProof, MerkleTree};
 
 /// Dictionary: the user original keys and values are hashed to be used in the leaf.
@@ -45,6 +45,20 @@ impl Dictionary {
     pub fn iter(&self) -> std::collections::hash_map::Iter<Value, Value> {
         self.mt.iter()
     }
+    /// Dictionary with the entries of both `self` and `other`.  A key present in both must have
+    /// the same value.
+    pub fn merge(&self, other: &Dictionary) -> Result<Dictionary> {
+        let mut kvs: HashMap<Value, Value> = self.iter().map(|(&k, &v)| (k, v)).collect();
+        for (&k, &v) in other.iter() {
+            match kvs.insert(k, v) {
+                Some(prev) if prev != v => return Err(Pod2Error::KeyAlreadyExists(k)),
+                _ => {}
+            }
+        }
+        Ok(Self {
+            mt: MerkleTree::new(&kvs),
+        })
+    }
 }
 impl<'a> IntoIterator for &'a Dictionary {
     type Item = (&'a Value, &'a Value);
@@ -68,6 +82,24 @@ impl Eq for Dictionary {}
 #[derive(Clone, Debug)]
 pub struct Set {
     mt: MerkleTree,
+    /// Number of distinct elements.  Not bound by the commitment, see [Set::prove_cardinality].
+    len: usize,
+}
+
+/// Membership proofs of distinct leaves of a [Set], showing that it has at least `len()`
+/// elements.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CardinalityProof {
+    leaves: Vec<(Value, MerkleProof)>,
+}
+
+impl CardinalityProof {
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
 }
 
 impl Set {
@@ -81,11 +113,48 @@ impl Set {
             .collect();
         Self {
             mt: MerkleTree::new(&kvs),
+            len: kvs.len(),
         }
     }
     pub fn commitment(&self) -> Hash {
         self.mt.root()
     }
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Proves that the set has at least `len()` elements by opening every leaf.  The leaves hold
+    /// the element hashes, so the elements themselves aren't revealed (unless they can be
+    /// guessed).
+    ///
+    /// The merkle tree doesn't commit to its number of leaves, so the count only reaches a
+    /// statement like `Gteq` through an entry holding `len()` next to the commitment: whoever
+    /// creates that entry (e.g. the signer of the pod) is trusted to have checked this proof.
+    pub fn prove_cardinality(&self) -> Result<CardinalityProof> {
+        let leaves = self
+            .mt
+            .iter()
+            .map(|(k, _)| Ok((*k, self.mt.prove(k)?)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(CardinalityProof { leaves })
+    }
+    /// Verifies a [CardinalityProof] against a set commitment, returning the number of distinct
+    /// leaves proven, which is a lower bound of the set size.
+    pub fn verify_cardinality(root: Hash, proof: &CardinalityProof) -> Result<usize> {
+        let mut seen = HashSet::new();
+        for (leaf, leaf_proof) in &proof.leaves {
+            if !seen.insert(*leaf) {
+                return Err(Pod2Error::ProofInvalid(format!(
+                    "leaf {:?} is proven more than once",
+                    leaf
+                )));
+            }
+            MerkleTree::verify(root, leaf_proof, leaf, &EMPTY)?;
+        }
+        Ok(seen.len())
+    }
     pub fn contains(&self, value: &Value) -> bool {
         self.mt.contains(value)
     }
@@ -157,3 +226,69 @@ impl PartialEq for Array {
     }
 }
 impl Eq for Array {}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::middleware::hash_str;
+
+    #[test]
+    fn test_dictionary_merge() -> Result<()> {
+        let dict = |kvs: &[(&str, i64)]| {
+            Dictionary::new(
+                &kvs.iter()
+                    .map(|(k, v)| (hash_str(k), Value::from(*v)))
+                    .collect(),
+            )
+        };
+        let a = dict(&[("name", 1), ("age", 30)]);
+        let b = dict(&[("country", 2)]);
+        let merged = a.merge(&b)?;
+        assert_eq!(merged, dict(&[("name", 1), ("age", 30), ("country", 2)]));
+        assert_eq!(merged.get(&Value(hash_str("country").0))?, Value::from(2));
+
+        // Equal duplicates are kept once
+        let c = dict(&[("age", 30), ("country", 2)]);
+        assert_eq!(merged.merge(&c)?, merged);
+
+        // Conflicting values for the same key
+        let d = dict(&[("age", 31)]);
+        assert_eq!(
+            a.merge(&d),
+            Err(Pod2Error::KeyAlreadyExists(Value(hash_str("age").0)))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_cardinality() -> Result<()> {
+        let elements = (0i64..5).map(Value::from).collect::<Vec<_>>();
+        let set = Set::new(&elements);
+        assert_eq!(set.len(), 5);
+        // Repeated elements are counted once
+        assert_eq!(
+            Set::new(&[elements.clone(), elements.clone()].concat()).len(),
+            5
+        );
+        assert!(Set::new(&vec![]).is_empty());
+
+        let proof = set.prove_cardinality()?;
+        assert_eq!(proof.len(), 5);
+        let len = Set::verify_cardinality(set.commitment(), &proof)?;
+        assert!(len >= 3);
+
+        // Proving the same leaf twice doesn't count
+        let mut bad_proof = proof.clone();
+        bad_proof.leaves[1] = bad_proof.leaves[0].clone();
+        assert!(matches!(
+            Set::verify_cardinality(set.commitment(), &bad_proof),
+            Err(Pod2Error::ProofInvalid(_))
+        ));
+        // The proof is bound to the set commitment
+        let other = Set::new(&elements[..3].to_vec());
+        assert!(Set::verify_cardinality(other.commitment(), &proof).is_err());
+
+        Ok(())
+    }
+}
@@ -4,19 +4,25 @@
 mod operation;
 mod statement;
 
-use anyhow::{anyhow, Error, Result};
+use anyhow::Result;
 use dyn_clone::DynClone;
 use hex::{FromHex, FromHexError};
+use itertools::Itertools;
 pub use operation::*;
 use plonky2::field::goldilocks_field::GoldilocksField;
-use plonky2::field::types::{Field, PrimeField64};
+use plonky2::field::types::{Field, Field64, PrimeField64};
 use plonky2::hash::poseidon::PoseidonHash;
 use plonky2::plonk::config::{Hasher, PoseidonGoldilocksConfig};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 pub use statement::*;
 use std::any::Any;
+use std::cell::RefCell;
 use std::cmp::{Ord, Ordering};
 use std::collections::HashMap;
 use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Pod2Error, VerifyError};
 
 pub mod containers;
 
@@ -27,7 +33,7 @@ pub type C = PoseidonGoldilocksConfig;
 /// D defines the extension degree of the field used in the Plonky2 proofs (quadratic extension).
 pub const D: usize = 2;
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 /// AnchoredKey is a tuple containing (OriginId: PodId, key: Hash)
 pub struct AnchoredKey(pub PodId, pub Hash);
 
@@ -81,32 +87,31 @@ impl From<Hash> for Value {
 }
 
 impl TryInto<i64> for Value {
-    type Error = Error;
+    type Error = Pod2Error;
     fn try_into(self) -> std::result::Result<i64, Self::Error> {
         let value = self.0;
         if &value[2..] != &[F::ZERO, F::ZERO]
             || value[..2]
                 .iter()
-                .all(|x| x.to_canonical_u64() > u32::MAX as u64)
+                .any(|x| x.to_canonical_u64() > u32::MAX as u64)
         {
-            Err(anyhow!("Value not an element of the i64 embedding."))
+            Err(Pod2Error::NotAnInteger(self))
         } else {
-            Ok((value[0].to_canonical_u64() + value[1].to_canonical_u64() << 32) as i64)
+            Ok((value[0].to_canonical_u64() + (value[1].to_canonical_u64() << 32)) as i64)
         }
     }
 }
 
+/// A value doesn't carry its type, so anything in the i64 embedding is displayed as an integer
+/// and the rest as a hash.  This means a hash with zero high limbs (e.g. from a string) can still
+/// be displayed as an integer.  Use the frontend `Value` to display typed values.
+// TODO: Dispatch on the type tag once middleware values carry one
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.0[2].is_zero() && self.0[3].is_zero() {
-            // Assume this is an integer
-            let (l0, l1) = (self.0[0].to_canonical_u64(), self.0[1].to_canonical_u64());
-            assert!(l0 < (1 << 32));
-            assert!(l1 < (1 << 32));
-            write!(f, "{}", l0 + l1 * (1 << 32))
-        } else {
-            // Assume this is a hash
-            Hash(self.0).fmt(f)
+        let int: std::result::Result<i64, _> = (*self).try_into();
+        match int {
+            Ok(int) => write!(f, "{}", int),
+            Err(_) => Hash(self.0).fmt(f),
         }
     }
 }
@@ -132,11 +137,82 @@ impl PartialOrd for Hash {
     }
 }
 
+/// Four uniformly random canonical field elements.
+#[cfg(any(test, feature = "test-util"))]
+fn rand_limbs<R: rand::Rng>(rng: &mut R) -> [F; 4] {
+    std::array::from_fn(|_| F::from_canonical_u64(rng.gen_range(0..F::ORDER)))
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl Value {
+    pub fn rand<R: rand::Rng>(rng: &mut R) -> Self {
+        Value(rand_limbs(rng))
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl Hash {
+    pub fn rand<R: rand::Rng>(rng: &mut R) -> Self {
+        Hash(rand_limbs(rng))
+    }
+}
+
+/// Serialize the four limbs as their canonical `u64` representation (little-endian order).
+fn serialize_limbs<S: Serializer>(limbs: &[F; 4], serializer: S) -> Result<S::Ok, S::Error> {
+    limbs.map(|f| f.to_canonical_u64()).serialize(serializer)
+}
+
+/// Deserialize four `u64` limbs, rejecting values that aren't canonical field elements.
+fn deserialize_limbs<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[F; 4], D::Error> {
+    let limbs = <[u64; 4]>::deserialize(deserializer)?;
+    if let Some(l) = limbs.iter().find(|&&l| l >= F::ORDER) {
+        return Err(de::Error::custom(format!(
+            "{} is not a canonical field element",
+            l
+        )));
+    }
+    Ok(limbs.map(F::from_canonical_u64))
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_limbs(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_limbs(deserializer).map(Value)
+    }
+}
+
+impl Serialize for Hash {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_limbs(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Hash {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_limbs(deserializer).map(Hash)
+    }
+}
+
 pub const EMPTY: Value = Value([F::ZERO, F::ZERO, F::ZERO, F::ZERO]);
 pub const NULL: Hash = Hash([F::ZERO, F::ZERO, F::ZERO, F::ZERO]);
 
+/// Displays the first 4 bytes of the hash, or with `{:#}` the full hash as parsed by
+/// `Hash::from_hex`.
 impl fmt::Display for Hash {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            for limb in self.0 {
+                for byte in limb.to_canonical_u64().to_le_bytes() {
+                    write!(f, "{:02x}", byte)?;
+                }
+            }
+            return Ok(());
+        }
         let v0 = self.0[0].to_canonical_u64();
         for i in 0..4 {
             write!(f, "{:02x}", (v0 >> (i * 8)) & 0xff)?;
@@ -161,7 +237,15 @@ impl FromHex for Hash {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+impl FromStr for Hash {
+    type Err = FromHexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub struct PodId(pub Hash);
 
 impl ToFields for PodId {
@@ -179,7 +263,19 @@ impl fmt::Display for PodId {
         } else if self.0 == NULL {
             write!(f, "null")
         } else {
-            write!(f, "{}", self.0)
+            fmt::Display::fmt(&self.0, f)
+        }
+    }
+}
+
+impl FromStr for PodId {
+    type Err = FromHexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "self" => Ok(SELF),
+            "null" => Ok(PodId(NULL)),
+            _ => Ok(PodId(s.parse()?)),
         }
     }
 }
@@ -216,7 +312,33 @@ pub fn hash_str(s: &str) -> Hash {
     Hash(PoseidonHash::hash_no_pad(&input).elements)
 }
 
-#[derive(Clone, Debug, Copy)]
+/// Maximum number of strings kept by the [hash_str_cached] cache of each thread.
+const HASH_STR_CACHE_CAP: usize = 1024;
+
+thread_local! {
+    static HASH_STR_CACHE: RefCell<HashMap<String, Hash>> = RefCell::new(HashMap::new());
+}
+
+/// Same as [hash_str], but remembers the hashes of the strings seen by the current thread.
+/// Meant for key names, which come from a small set and are hashed over and over.  The cache is
+/// cleared once it holds `HASH_STR_CACHE_CAP` strings, so that hashing many distinct strings
+/// doesn't grow it without bound.
+pub fn hash_str_cached(s: &str) -> Hash {
+    if let Some(h) = HASH_STR_CACHE.with(|cache| cache.borrow().get(s).copied()) {
+        return h;
+    }
+    let h = hash_str(s);
+    HASH_STR_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.len() >= HASH_STR_CACHE_CAP {
+            cache.clear();
+        }
+        cache.insert(s.to_string(), h);
+    });
+    h
+}
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
 pub struct Params {
     pub max_input_signed_pods: usize,
     pub max_input_main_pods: usize,
@@ -228,9 +350,87 @@ pub struct Params {
 }
 
 impl Params {
+    /// Builder to override some of the default params, e.g.
+    /// `Params::builder().max_statements(40).build()`.
+    pub fn builder() -> ParamsBuilder {
+        ParamsBuilder::default()
+    }
+
     pub fn max_priv_statements(&self) -> usize {
         self.max_statements - self.max_public_statements
     }
+
+    /// Check that the limits are consistent with each other and with the pod layout.
+    pub fn validate(&self) -> Result<(), Pod2Error> {
+        let invalid = |msg: String| Err(Pod2Error::InvalidParams(msg));
+        if self.max_statement_args == 0 || self.max_operation_args == 0 {
+            return invalid("max_statement_args and max_operation_args must be non-zero".into());
+        }
+        // SumOf, ProductOf, MaxOf, ... take 3 arguments
+        if self.max_statement_args < 3 {
+            return invalid(format!(
+                "max_statement_args ({}) must be at least 3",
+                self.max_statement_args
+            ));
+        }
+        if self.max_operation_args < 3 {
+            return invalid(format!(
+                "max_operation_args ({}) must be at least 3",
+                self.max_operation_args
+            ));
+        }
+        // The first public slot holds the pod type statement
+        if self.max_public_statements == 0 {
+            return invalid("max_public_statements must be non-zero".into());
+        }
+        if self.max_public_statements > self.max_statements {
+            return invalid(format!(
+                "max_public_statements ({}) exceeds max_statements ({})",
+                self.max_public_statements, self.max_statements
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Builder for [Params] that starts from the defaults, see [Params::builder].
+#[derive(Clone, Debug, Default)]
+pub struct ParamsBuilder {
+    params: Params,
+}
+
+impl ParamsBuilder {
+    pub fn max_input_signed_pods(mut self, max_input_signed_pods: usize) -> Self {
+        self.params.max_input_signed_pods = max_input_signed_pods;
+        self
+    }
+    pub fn max_input_main_pods(mut self, max_input_main_pods: usize) -> Self {
+        self.params.max_input_main_pods = max_input_main_pods;
+        self
+    }
+    pub fn max_statements(mut self, max_statements: usize) -> Self {
+        self.params.max_statements = max_statements;
+        self
+    }
+    pub fn max_signed_pod_values(mut self, max_signed_pod_values: usize) -> Self {
+        self.params.max_signed_pod_values = max_signed_pod_values;
+        self
+    }
+    pub fn max_public_statements(mut self, max_public_statements: usize) -> Self {
+        self.params.max_public_statements = max_public_statements;
+        self
+    }
+    pub fn max_statement_args(mut self, max_statement_args: usize) -> Self {
+        self.params.max_statement_args = max_statement_args;
+        self
+    }
+    pub fn max_operation_args(mut self, max_operation_args: usize) -> Self {
+        self.params.max_operation_args = max_operation_args;
+        self
+    }
+    pub fn build(self) -> Params {
+        self.params
+    }
 }
 
 impl Default for Params {
@@ -249,8 +449,25 @@ impl Default for Params {
 
 pub trait Pod: fmt::Debug + DynClone {
     fn verify(&self) -> bool;
+    /// Like `verify`, but telling why the pod is invalid.  Backends that can't tell only
+    /// return `VerifyError::Invalid`.
+    fn verify_detailed(&self) -> Result<(), VerifyError> {
+        if self.verify() {
+            Ok(())
+        } else {
+            Err(VerifyError::Invalid)
+        }
+    }
     fn id(&self) -> PodId;
+    /// Id computed from the contents of the pod.  `verify` checks that it matches `id`, so that
+    /// a pod can't claim an id that doesn't correspond to its statements.
+    fn id_from_content(&self) -> PodId;
     fn pub_statements(&self) -> Vec<Statement>;
+    /// Operations justifying the public statements, in the same order as `pub_statements`.
+    /// Backends that don't keep the operations return an empty list.
+    fn pub_operations(&self) -> Vec<Operation> {
+        Vec::new()
+    }
     /// Extract key-values from ValueOf public statements
     fn kvs(&self) -> HashMap<AnchoredKey, Value> {
         self.pub_statements()
@@ -261,6 +478,40 @@ pub trait Pod: fmt::Debug + DynClone {
             })
             .collect()
     }
+    /// Anchored keys referenced by the statements of the pod, sorted and without duplicates.
+    /// Backends that keep the private statements also include their keys.
+    fn referenced_keys(&self) -> Vec<AnchoredKey> {
+        self.pub_statements()
+            .into_iter()
+            .flat_map(|st| st.args())
+            .filter_map(|arg| match arg {
+                StatementArg::Key(ak) => Some(ak),
+                _ => None,
+            })
+            .sorted_by_key(|ak| (ak.0 .0, ak.1))
+            .dedup()
+            .collect()
+    }
+    /// GraphViz DOT rendering of how the statements of the pod derive from each other, or
+    /// `None` if the backend doesn't keep the derivation.
+    fn to_graphviz(&self) -> Option<String> {
+        None
+    }
+    /// Serialized pod, tagged by backend so that it can be loaded with
+    /// `backends::signed_pod_from_json`.  `None` if the backend doesn't support serialization.
+    fn to_json(&self) -> Option<serde_json::Value> {
+        None
+    }
+    /// Value at the `KEY_SIGNER` key of the pod, if it has one.
+    fn signer(&self) -> Option<Value> {
+        self.kvs()
+            .get(&AnchoredKey(self.id(), hash_str(KEY_SIGNER)))
+            .cloned()
+    }
+    /// Canonical field encoding of the public statements, see [canonical_statements_to_fields].
+    fn to_fields(&self) -> Vec<F> {
+        canonical_statements_to_fields(&self.pub_statements())
+    }
     // Used for downcasting
     fn into_any(self: Box<Self>) -> Box<dyn Any>;
 }
@@ -270,6 +521,11 @@ dyn_clone::clone_trait_object!(Pod);
 
 pub trait PodSigner {
     fn sign(&mut self, params: &Params, kvs: &HashMap<Hash, Value>) -> Result<Box<dyn Pod>>;
+    /// Public key of the signer, as committed in the pods it signs.  `None` for signers that
+    /// don't have a single public key.
+    fn pubkey(&self) -> Option<Value> {
+        None
+    }
 }
 
 /// This is a filler type that fulfills the Pod trait and always verifies.  It's empty.  This
@@ -284,6 +540,9 @@ impl Pod for NonePod {
     fn id(&self) -> PodId {
         PodId(NULL)
     }
+    fn id_from_content(&self) -> PodId {
+        PodId(NULL)
+    }
     fn pub_statements(&self) -> Vec<Statement> {
         Vec::new()
     }
@@ -307,8 +566,160 @@ pub trait PodProver {
     fn prove(&mut self, params: &Params, inputs: MainPodInputs) -> Result<Box<dyn Pod>>;
 }
 
+/// Canonical field encoding of a list of statements: the number of statements followed by the
+/// length and the fields of each statement.
+pub fn statements_to_fields(statements: &[Statement]) -> Vec<F> {
+    encodings_to_fields(statements.iter().map(|st| st.to_fields_ref()).collect())
+}
+
+/// Like [statements_to_fields], with the statements sorted by their encoding so that it doesn't
+/// depend on their order.
+pub fn canonical_statements_to_fields(statements: &[Statement]) -> Vec<F> {
+    let mut encodings: Vec<_> = statements.iter().map(|st| st.to_fields_ref()).collect();
+    encodings.sort_by_cached_key(|(fields, _)| {
+        fields
+            .iter()
+            .map(|f| f.to_canonical_u64())
+            .collect::<Vec<_>>()
+    });
+    encodings_to_fields(encodings)
+}
+
+/// Length-prefixed concatenation of already computed statement encodings, so that each
+/// statement is encoded only once.
+fn encodings_to_fields(encodings: Vec<(Vec<F>, usize)>) -> Vec<F> {
+    let mut fields = vec![F::from_canonical_usize(encodings.len())];
+    for (st_fields, len) in encodings {
+        fields.push(F::from_canonical_usize(len));
+        fields.extend(st_fields);
+    }
+    fields
+}
+
 pub trait ToFields {
     /// returns Vec<F> representation of the type, and a usize indicating how many field elements
     /// does the vector contain
     fn to_fields(self) -> (Vec<F>, usize);
 }
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_display() {
+        assert_eq!(Value::from(42).to_string(), "42");
+        assert_eq!(Value::from(-42).to_string(), "-42");
+        let hash = hash_str("foo");
+        assert_eq!(Value(hash.0).to_string(), hash.to_string());
+        // Zero high limbs but outside of the i64 embedding
+        let value = Value([F::from_canonical_u64(1 << 40), F::ZERO, F::ZERO, F::ZERO]);
+        assert_eq!(value.to_string(), Hash(value.0).to_string());
+    }
+
+    #[test]
+    fn test_value_hash_serde() -> Result<()> {
+        let value = Value::from(-42);
+        let json = serde_json::to_string(&value)?;
+        assert_eq!(json, "[4294967254,4294967295,0,0]");
+        assert_eq!(serde_json::from_str::<Value>(&json)?, value);
+
+        let hash = hash_str("foo");
+        let json = serde_json::to_string(&hash)?;
+        assert_eq!(serde_json::from_str::<Hash>(&json)?, hash);
+
+        // Non-canonical limbs are rejected
+        let json = format!("[{},0,0,0]", F::ORDER);
+        assert!(serde_json::from_str::<Value>(&json).is_err());
+        assert!(serde_json::from_str::<Hash>(&json).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_pod_id_from_str() -> Result<()> {
+        let hash = hash_str("foo");
+        let full = format!("{:#}", hash);
+        assert_eq!(full.len(), 64);
+        assert!(full.starts_with(&format!("{}", hash).trim_end_matches('…')));
+        assert_eq!(full.parse::<Hash>()?, hash);
+        assert!("abc".parse::<Hash>().is_err());
+
+        for id in [PodId(hash), SELF, PodId(NULL)] {
+            assert_eq!(format!("{:#}", id).parse::<PodId>()?, id);
+        }
+        assert_eq!(format!("{:#}", SELF), "self");
+        assert_eq!(format!("{:#}", PodId(NULL)), "null");
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_str_cached() {
+        let keys = ["_signer", "_type", "socialSecurityNumber", "dateOfBirth"];
+        let cache_len = || HASH_STR_CACHE.with(|cache| cache.borrow().len());
+        let before = cache_len();
+        for _ in 0..100 {
+            for key in keys {
+                assert_eq!(hash_str_cached(key), hash_str(key));
+            }
+        }
+        // One Poseidon invocation per distinct key, the rest are cache hits
+        assert_eq!(cache_len() - before, keys.len());
+
+        // Many distinct strings don't grow the cache past its cap
+        for i in 0..2 * HASH_STR_CACHE_CAP {
+            let key = format!("key{}", i);
+            assert_eq!(hash_str_cached(&key), hash_str(&key));
+            assert!(cache_len() <= HASH_STR_CACHE_CAP);
+        }
+    }
+
+    #[test]
+    fn test_params_validate() {
+        assert_eq!(Params::default().validate(), Ok(()));
+        let invalid = [
+            Params {
+                max_public_statements: 21,
+                ..Default::default()
+            },
+            Params {
+                max_public_statements: 0,
+                ..Default::default()
+            },
+            Params {
+                max_statement_args: 0,
+                ..Default::default()
+            },
+            Params {
+                max_operation_args: 0,
+                ..Default::default()
+            },
+            Params {
+                max_statement_args: 2,
+                ..Default::default()
+            },
+            Params {
+                max_operation_args: 2,
+                ..Default::default()
+            },
+        ];
+        for params in invalid {
+            assert!(matches!(
+                params.validate(),
+                Err(Pod2Error::InvalidParams(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_params_builder() {
+        assert_eq!(Params::builder().build(), Params::default());
+        let params = Params::builder().max_statements(40).build();
+        assert_eq!(
+            params,
+            Params {
+                max_statements: 40,
+                ..Default::default()
+            }
+        );
+    }
+}
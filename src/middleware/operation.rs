@@ -1,9 +1,14 @@
-use crate::middleware::{AnchoredKey, SELF};
-use anyhow::{anyhow, Result};
+use crate::middleware::{containers::Dictionary, AnchoredKey, Hash, Value, SELF};
+use crate::primitives::merkletree::MerkleProof;
+use anyhow::Result;
+use std::fmt;
+
+use crate::error::Pod2Error;
+use strum_macros::FromRepr;
 
 use super::Statement;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, FromRepr, PartialEq, Eq)]
 pub enum NativeOperation {
     None = 0,
     NewEntry = 1,
@@ -21,6 +26,90 @@ pub enum NativeOperation {
     SumOf = 13,
     ProductOf = 14,
     MaxOf = 15,
+    GteqFromEntries = 16,
+    LteqFromEntries = 17,
+    EqualToLiteral = 18,
+    TransitiveEqualFromChain = 19,
+    DivOf = 20,
+    ModOf = 21,
+    AndOf = 22,
+    OrOf = 23,
+    NotOf = 24,
+    InRangeFromEntries = 25,
+    EqualsContainedValue = 26,
+    GtToLiteral = 27,
+    LtToLiteral = 28,
+}
+
+/// Error returned when forming or checking an [Operation].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OperationError {
+    /// The operation code was given a wrong number or kind of arguments.
+    ArityMismatch(NativeOperation, usize),
+    /// An argument value doesn't have the type required by the operation.
+    TypeMismatch(String),
+    /// The operation doesn't deduce the given output statement.
+    DeductionFailed(String),
+    /// A proof carried by the operation doesn't verify.
+    ProofInvalid(String),
+}
+
+impl fmt::Display for OperationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OperationError::ArityMismatch(op_code, n) => {
+                write!(f, "Ill-formed operation {:?} with {} arguments", op_code, n)
+            }
+            OperationError::TypeMismatch(msg) => write!(f, "Type mismatch: {}", msg),
+            OperationError::DeductionFailed(msg) => write!(f, "Invalid deduction: {}", msg),
+            OperationError::ProofInvalid(msg) => write!(f, "Invalid proof: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for OperationError {}
+
+/// Interprets a value as an integer operand of an arithmetic operation.
+fn int_value(v: &Value) -> Result<i64, OperationError> {
+    (*v).try_into()
+        .map_err(|e: Pod2Error| OperationError::TypeMismatch(e.to_string()))
+}
+
+/// Interprets a value as a boolean operand of a logical operation.
+fn bool_value(v: &Value) -> Result<bool, OperationError> {
+    match int_value(v)? {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(OperationError::TypeMismatch(format!(
+            "{} is not a boolean",
+            v
+        ))),
+    }
+}
+
+/// Whether two pairs of keys are equal regardless of their order, for the symmetric predicates.
+fn same_pair(p1: (&AnchoredKey, &AnchoredKey), p2: (&AnchoredKey, &AnchoredKey)) -> bool {
+    p1 == p2 || (p1.0 == p2.1 && p1.1 == p2.0)
+}
+
+/// Whether the `Equal` links, taken in order and each in either direction, connect `from` to
+/// `to`.
+fn equal_chain(
+    links: &[(&AnchoredKey, &AnchoredKey)],
+    from: &AnchoredKey,
+    to: &AnchoredKey,
+) -> bool {
+    let mut current = from;
+    for &(ak1, ak2) in links {
+        current = if ak1 == current {
+            ak2
+        } else if ak2 == current {
+            ak1
+        } else {
+            return false;
+        };
+    }
+    current == to
 }
 
 // TODO: Refine this enum.
@@ -42,6 +131,21 @@ pub enum Operation {
     SumOf(Statement, Statement, Statement),
     ProductOf(Statement, Statement, Statement),
     MaxOf(Statement, Statement, Statement),
+    GteqFromEntries(Statement, Statement),
+    LteqFromEntries(Statement, Statement),
+    EqualToLiteral(Statement),
+    TransitiveEqualFromChain(Vec<Statement>),
+    DivOf(Statement, Statement, Statement),
+    ModOf(Statement, Statement, Statement),
+    AndOf(Statement, Statement, Statement),
+    OrOf(Statement, Statement, Statement),
+    NotOf(Statement, Statement),
+    InRangeFromEntries(Statement),
+    /// From `ValueOf(ak1, v)` and `ValueOf(ak2, root)`, with a proof that the dictionary with
+    /// commitment `root` maps some key to `v`.
+    EqualsContainedValue(Statement, Statement, MerkleProof),
+    GtToLiteral(Statement),
+    LtToLiteral(Statement),
 }
 
 impl Operation {
@@ -64,6 +168,19 @@ impl Operation {
             Self::SumOf(_, _, _) => SumOf,
             Self::ProductOf(_, _, _) => ProductOf,
             Self::MaxOf(_, _, _) => MaxOf,
+            Self::GteqFromEntries(_, _) => GteqFromEntries,
+            Self::LteqFromEntries(_, _) => LteqFromEntries,
+            Self::EqualToLiteral(_) => EqualToLiteral,
+            Self::TransitiveEqualFromChain(_) => TransitiveEqualFromChain,
+            Self::DivOf(_, _, _) => DivOf,
+            Self::ModOf(_, _, _) => ModOf,
+            Self::AndOf(_, _, _) => AndOf,
+            Self::OrOf(_, _, _) => OrOf,
+            Self::NotOf(_, _) => NotOf,
+            Self::InRangeFromEntries(_) => InRangeFromEntries,
+            Self::EqualsContainedValue(_, _, _) => EqualsContainedValue,
+            Self::GtToLiteral(_) => GtToLiteral,
+            Self::LtToLiteral(_) => LtToLiteral,
         }
     }
 
@@ -85,10 +202,23 @@ impl Operation {
             Self::SumOf(s1, s2, s3) => vec![s1, s2, s3],
             Self::ProductOf(s1, s2, s3) => vec![s1, s2, s3],
             Self::MaxOf(s1, s2, s3) => vec![s1, s2, s3],
+            Self::GteqFromEntries(s1, s2) => vec![s1, s2],
+            Self::LteqFromEntries(s1, s2) => vec![s1, s2],
+            Self::EqualToLiteral(s) => vec![s],
+            Self::TransitiveEqualFromChain(sts) => sts,
+            Self::DivOf(s1, s2, s3) => vec![s1, s2, s3],
+            Self::ModOf(s1, s2, s3) => vec![s1, s2, s3],
+            Self::AndOf(s1, s2, s3) => vec![s1, s2, s3],
+            Self::OrOf(s1, s2, s3) => vec![s1, s2, s3],
+            Self::NotOf(s1, s2) => vec![s1, s2],
+            Self::InRangeFromEntries(s) => vec![s],
+            Self::EqualsContainedValue(s1, s2, _) => vec![s1, s2],
+            Self::GtToLiteral(s) => vec![s],
+            Self::LtToLiteral(s) => vec![s],
         }
     }
     /// Forms operation from op-code and arguments.
-    pub fn op(op_code: NativeOperation, args: &[Statement]) -> Result<Self> {
+    pub fn op(op_code: NativeOperation, args: &[Statement]) -> Result<Self, OperationError> {
         type NO = NativeOperation;
         let arg_tup = (
             args.get(0).cloned(),
@@ -105,6 +235,13 @@ impl Operation {
             }
             (NO::GtFromEntries, (Some(s1), Some(s2), None), 2) => Self::GtFromEntries(s1, s2),
             (NO::LtFromEntries, (Some(s1), Some(s2), None), 2) => Self::LtFromEntries(s1, s2),
+            (NO::GteqFromEntries, (Some(s1), Some(s2), None), 2) => Self::GteqFromEntries(s1, s2),
+            (NO::LteqFromEntries, (Some(s1), Some(s2), None), 2) => Self::LteqFromEntries(s1, s2),
+            (NO::TransitiveEqualFromStatements, (Some(s1), Some(s2), None), 2) => {
+                Self::TransitiveEqualFromStatements(s1, s2)
+            }
+            (NO::GtToNotEqual, (Some(s), None, None), 1) => Self::GtToNotEqual(s),
+            (NO::LtToNotEqual, (Some(s), None, None), 1) => Self::LtToNotEqual(s),
             (NO::ContainsFromEntries, (Some(s1), Some(s2), None), 2) => {
                 Self::ContainsFromEntries(s1, s2)
             }
@@ -117,25 +254,53 @@ impl Operation {
             (NO::SumOf, (Some(s1), Some(s2), Some(s3)), 3) => Self::SumOf(s1, s2, s3),
             (NO::ProductOf, (Some(s1), Some(s2), Some(s3)), 3) => Self::ProductOf(s1, s2, s3),
             (NO::MaxOf, (Some(s1), Some(s2), Some(s3)), 3) => Self::MaxOf(s1, s2, s3),
-            _ => Err(anyhow!(
-                "Ill-formed operation {:?} with arguments {:?}.",
-                op_code,
-                args
-            ))?,
+            (NO::EqualToLiteral, (Some(s), None, None), 1) => Self::EqualToLiteral(s),
+            (NO::DivOf, (Some(s1), Some(s2), Some(s3)), 3) => Self::DivOf(s1, s2, s3),
+            (NO::ModOf, (Some(s1), Some(s2), Some(s3)), 3) => Self::ModOf(s1, s2, s3),
+            (NO::AndOf, (Some(s1), Some(s2), Some(s3)), 3) => Self::AndOf(s1, s2, s3),
+            (NO::OrOf, (Some(s1), Some(s2), Some(s3)), 3) => Self::OrOf(s1, s2, s3),
+            (NO::NotOf, (Some(s1), Some(s2), None), 2) => Self::NotOf(s1, s2),
+            (NO::InRangeFromEntries, (Some(s), None, None), 1) => Self::InRangeFromEntries(s),
+            (NO::GtToLiteral, (Some(s), None, None), 1) => Self::GtToLiteral(s),
+            (NO::LtToLiteral, (Some(s), None, None), 1) => Self::LtToLiteral(s),
+            (NO::TransitiveEqualFromChain, _, n) if n >= 2 => {
+                Self::TransitiveEqualFromChain(args.to_vec())
+            }
+            _ => Err(OperationError::ArityMismatch(op_code, args.len()))?,
         })
     }
+    /// Forms an operation that carries a merkle proof besides its statement arguments.
+    pub fn op_with_proof(
+        op_code: NativeOperation,
+        args: &[Statement],
+        proof: MerkleProof,
+    ) -> Result<Self, OperationError> {
+        match (op_code, args) {
+            (NativeOperation::EqualsContainedValue, [s1, s2]) => {
+                Ok(Self::EqualsContainedValue(s1.clone(), s2.clone(), proof))
+            }
+            _ => Err(OperationError::ArityMismatch(op_code, args.len())),
+        }
+    }
+    /// Merkle proof carried by the operation, if any.
+    pub fn merkle_proof(&self) -> Option<&MerkleProof> {
+        match self {
+            Self::EqualsContainedValue(_, _, proof) => Some(proof),
+            _ => None,
+        }
+    }
     /// Checks the given operation against a statement.
-    pub fn check(&self, output_statement: &Statement) -> Result<bool> {
+    pub fn check(&self, output_statement: &Statement) -> Result<bool, OperationError> {
         use Statement::*;
         match (self, output_statement) {
             (Self::None, None) => Ok(true),
             (Self::NewEntry, ValueOf(AnchoredKey(pod_id, _), _)) => Ok(pod_id == &SELF),
             (Self::CopyStatement(s1), s2) => Ok(s1 == s2),
             (Self::EqualFromEntries(ValueOf(ak1, v1), ValueOf(ak2, v2)), Equal(ak3, ak4)) => {
-                Ok(v1 == v2 && ak3 == ak1 && ak4 == ak2)
+                Ok(v1 == v2 && same_pair((ak3, ak4), (ak1, ak2)))
             }
             (Self::NotEqualFromEntries(ValueOf(ak1, v1), ValueOf(ak2, v2)), NotEqual(ak3, ak4)) => {
-                Ok(v1 != v2 && ak3 == ak1 && ak4 == ak2)
+                Ok(v1 != v2 && same_pair((ak3, ak4), (ak1, ak2)))
             }
             (Self::GtFromEntries(ValueOf(ak1, v1), ValueOf(ak2, v2)), Gt(ak3, ak4)) => {
                 Ok(v1 > v2 && ak3 == ak1 && ak4 == ak2)
@@ -143,6 +308,17 @@ impl Operation {
             (Self::LtFromEntries(ValueOf(ak1, v1), ValueOf(ak2, v2)), Lt(ak3, ak4)) => {
                 Ok(v1 < v2 && ak3 == ak1 && ak4 == ak2)
             }
+            (Self::GteqFromEntries(ValueOf(ak1, v1), ValueOf(ak2, v2)), Gteq(ak3, ak4)) => {
+                Ok(v1 >= v2 && ak3 == ak1 && ak4 == ak2)
+            }
+            (Self::LteqFromEntries(ValueOf(ak1, v1), ValueOf(ak2, v2)), Lteq(ak3, ak4)) => {
+                Ok(v1 <= v2 && ak3 == ak1 && ak4 == ak2)
+            }
+            (Self::EqualToLiteral(ValueOf(ak1, v1)), EqualLiteral(ak2, v2)) => {
+                Ok(v1 == v2 && ak1 == ak2)
+            }
+            (Self::GtToLiteral(ValueOf(ak1, v1)), GtLiteral(ak2, v2)) => Ok(v1 > v2 && ak1 == ak2),
+            (Self::LtToLiteral(ValueOf(ak1, v1)), LtLiteral(ak2, v2)) => Ok(v1 < v2 && ak1 == ak2),
             (Self::ContainsFromEntries(_, _), Contains(_, _)) =>
             /* TODO */
             {
@@ -156,26 +332,459 @@ impl Operation {
             (
                 Self::TransitiveEqualFromStatements(Equal(ak1, ak2), Equal(ak3, ak4)),
                 Equal(ak5, ak6),
-            ) => Ok(ak2 == ak3 && ak5 == ak1 && ak6 == ak4),
-            (Self::GtToNotEqual(Gt(ak1, ak2)), NotEqual(ak3, ak4)) => Ok(ak1 == ak3 && ak2 == ak4),
-            (Self::LtToNotEqual(Lt(ak1, ak2)), NotEqual(ak3, ak4)) => Ok(ak1 == ak3 && ak2 == ak4),
+            ) => {
+                let links = [(ak1, ak2), (ak3, ak4)];
+                Ok(equal_chain(&links, ak5, ak6) || equal_chain(&links, ak6, ak5))
+            }
+            (Self::TransitiveEqualFromChain(sts), Equal(ak1, ak2)) => {
+                let links = sts
+                    .iter()
+                    .map(|s| match s {
+                        Equal(ak3, ak4) => Ok((ak3, ak4)),
+                        _ => Err(OperationError::TypeMismatch(format!(
+                            "{} is not an Equal statement",
+                            s
+                        ))),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(equal_chain(&links, ak1, ak2) || equal_chain(&links, ak2, ak1))
+            }
+            (Self::GtToNotEqual(Gt(ak1, ak2)), NotEqual(ak3, ak4)) => {
+                Ok(same_pair((ak1, ak2), (ak3, ak4)))
+            }
+            (Self::LtToNotEqual(Lt(ak1, ak2)), NotEqual(ak3, ak4)) => {
+                Ok(same_pair((ak1, ak2), (ak3, ak4)))
+            }
             (Self::RenameContainedBy(Contains(ak1, ak2), Equal(ak3, ak4)), Contains(ak5, ak6)) => {
-                Ok(ak1 == ak3 && ak4 == ak5 && ak2 == ak6)
+                Ok(((ak1 == ak3 && ak5 == ak4) || (ak1 == ak4 && ak5 == ak3)) && ak2 == ak6)
             }
             (
                 Self::SumOf(ValueOf(ak1, v1), ValueOf(ak2, v2), ValueOf(ak3, v3)),
                 SumOf(ak4, ak5, ak6),
             ) => {
-                let v1: i64 = v1.clone().try_into()?;
-                let v2: i64 = v2.clone().try_into()?;
-                let v3: i64 = v3.clone().try_into()?;
-                Ok((v1 == v2 + v3) && ak4 == ak1 && ak5 == ak2 && ak6 == ak3)
-            }
-            _ => Err(anyhow!(
-                "Invalid deduction: {:?} ⇏ {:#}",
-                self,
-                output_statement
+                let (v1, v2, v3) = (int_value(v1)?, int_value(v2)?, int_value(v3)?);
+                Ok(v2.checked_add(v3) == Some(v1) && ak4 == ak1 && ak5 == ak2 && ak6 == ak3)
+            }
+            (
+                Self::ProductOf(ValueOf(ak1, v1), ValueOf(ak2, v2), ValueOf(ak3, v3)),
+                ProductOf(ak4, ak5, ak6),
+            ) => {
+                let (v1, v2, v3) = (int_value(v1)?, int_value(v2)?, int_value(v3)?);
+                Ok(v2.checked_mul(v3) == Some(v1) && ak4 == ak1 && ak5 == ak2 && ak6 == ak3)
+            }
+            (
+                Self::MaxOf(ValueOf(ak1, v1), ValueOf(ak2, v2), ValueOf(ak3, v3)),
+                MaxOf(ak4, ak5, ak6),
+            ) => {
+                let (v1, v2, v3) = (int_value(v1)?, int_value(v2)?, int_value(v3)?);
+                Ok((v1 == v2.max(v3)) && ak4 == ak1 && ak5 == ak2 && ak6 == ak3)
+            }
+            (
+                Self::DivOf(ValueOf(ak1, v1), ValueOf(ak2, v2), ValueOf(ak3, v3)),
+                DivOf(ak4, ak5, ak6),
+            ) => {
+                let (v1, v2, v3) = (int_value(v1)?, int_value(v2)?, int_value(v3)?);
+                if v3 == 0 {
+                    return Err(OperationError::DeductionFailed("division by zero".into()));
+                }
+                Ok(v2.checked_div(v3) == Some(v1) && ak4 == ak1 && ak5 == ak2 && ak6 == ak3)
+            }
+            (
+                Self::ModOf(ValueOf(ak1, v1), ValueOf(ak2, v2), ValueOf(ak3, v3)),
+                ModOf(ak4, ak5, ak6),
+            ) => {
+                let (v1, v2, v3) = (int_value(v1)?, int_value(v2)?, int_value(v3)?);
+                if v3 == 0 {
+                    return Err(OperationError::DeductionFailed("division by zero".into()));
+                }
+                Ok(v2.checked_rem(v3) == Some(v1) && ak4 == ak1 && ak5 == ak2 && ak6 == ak3)
+            }
+            (
+                Self::AndOf(ValueOf(ak1, v1), ValueOf(ak2, v2), ValueOf(ak3, v3)),
+                AndOf(ak4, ak5, ak6),
+            ) => {
+                let (v1, v2, v3) = (bool_value(v1)?, bool_value(v2)?, bool_value(v3)?);
+                Ok((v1 == (v2 && v3)) && ak4 == ak1 && ak5 == ak2 && ak6 == ak3)
+            }
+            (
+                Self::OrOf(ValueOf(ak1, v1), ValueOf(ak2, v2), ValueOf(ak3, v3)),
+                OrOf(ak4, ak5, ak6),
+            ) => {
+                let (v1, v2, v3) = (bool_value(v1)?, bool_value(v2)?, bool_value(v3)?);
+                Ok((v1 == (v2 || v3)) && ak4 == ak1 && ak5 == ak2 && ak6 == ak3)
+            }
+            (Self::NotOf(ValueOf(ak1, v1), ValueOf(ak2, v2)), NotOf(ak3, ak4)) => {
+                let (v1, v2) = (bool_value(v1)?, bool_value(v2)?);
+                Ok((v1 == !v2) && ak3 == ak1 && ak4 == ak2)
+            }
+            (Self::InRangeFromEntries(ValueOf(ak1, v)), InRange(ak2, min, max)) => {
+                Ok(min <= v && v <= max && ak1 == ak2)
+            }
+            (
+                Self::EqualsContainedValue(ValueOf(ak1, v), ValueOf(ak2, root), proof),
+                EqualsContainedValue(ak3, ak4, key),
+            ) => {
+                Dictionary::verify(Hash(root.0), proof, key, v)
+                    .map_err(|e| OperationError::ProofInvalid(e.to_string()))?;
+                Ok(ak1 == ak3 && ak2 == ak4)
+            }
+            _ => Err(OperationError::DeductionFailed(format!(
+                "{:?} ⇏ {:#}",
+                self, output_statement
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::middleware::hash_str;
+    use std::collections::HashMap;
+
+    fn value_of(key: &str, v: i64) -> (AnchoredKey, Statement) {
+        let ak = AnchoredKey(SELF, hash_str(key));
+        (ak.clone(), Statement::ValueOf(ak, Value::from(v)))
+    }
+
+    #[test]
+    fn test_gteq_lteq() -> Result<()> {
+        let (ak1, st1) = value_of("a", 42);
+        let (ak2, st2) = value_of("b", 18);
+        let (ak3, st3) = value_of("c", 42);
+
+        let gteq = Statement::Gteq(ak1.clone(), ak2.clone());
+        assert!(Operation::GteqFromEntries(st1.clone(), st2.clone()).check(&gteq)?);
+        assert!(!Operation::GteqFromEntries(st2.clone(), st1.clone())
+            .check(&Statement::Gteq(ak2.clone(), ak1.clone()))?);
+
+        let lteq = Statement::Lteq(ak2.clone(), ak1.clone());
+        assert!(Operation::LteqFromEntries(st2.clone(), st1.clone()).check(&lteq)?);
+        assert!(!Operation::LteqFromEntries(st1.clone(), st2.clone())
+            .check(&Statement::Lteq(ak1.clone(), ak2.clone()))?);
+
+        // Boundary case: v1 == v2 satisfies both.
+        assert!(Operation::GteqFromEntries(st1.clone(), st3.clone())
+            .check(&Statement::Gteq(ak1.clone(), ak3.clone()))?);
+        assert!(Operation::LteqFromEntries(st1.clone(), st3.clone())
+            .check(&Statement::Lteq(ak1.clone(), ak3.clone()))?);
+
+        // The output keys must match the input keys.
+        assert!(!Operation::GteqFromEntries(st1, st3).check(&gteq)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_operation_errors() -> Result<()> {
+        let (ak1, st1) = value_of("a", 3);
+        let (ak2, st2) = value_of("b", 1);
+        let (ak3, st3) = value_of("c", 2);
+
+        // Ill-formed operation
+        assert_eq!(
+            Operation::op(NativeOperation::EqualFromEntries, &[st1.clone()]),
+            Err(OperationError::ArityMismatch(
+                NativeOperation::EqualFromEntries,
+                1
+            ))
+        );
+
+        // Non-integer operand in an arithmetic operation
+        let st_hash = Statement::ValueOf(ak3.clone(), Value::from(hash_str("two")));
+        assert!(matches!(
+            Operation::SumOf(st1.clone(), st2.clone(), st_hash).check(&Statement::SumOf(
+                ak1.clone(),
+                ak2.clone(),
+                ak3.clone()
             )),
+            Err(OperationError::TypeMismatch(_))
+        ));
+
+        // Operation that doesn't deduce the output statement
+        assert!(matches!(
+            Operation::EqualFromEntries(st1.clone(), st2.clone())
+                .check(&Statement::Gt(ak1.clone(), ak2.clone())),
+            Err(OperationError::DeductionFailed(_))
+        ));
+
+        // Well-formed operation with a false arithmetic relation
+        assert!(
+            !Operation::SumOf(st2.clone(), st1, st3).check(&Statement::SumOf(
+                ak2.clone(),
+                ak1,
+                ak3
+            ))?
+        );
+
+        // An overflowing sum is a failed check, not a panic or a wrapped result
+        let (ak_max, st_max) = value_of("max", i64::MAX);
+        let (ak_min, st_min) = value_of("min", i64::MIN);
+        assert!(
+            !Operation::SumOf(st_min, st_max, st2).check(&Statement::SumOf(ak_min, ak_max, ak2))?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_equal_to_literal() -> Result<()> {
+        let (ak, st) = value_of("country", 1);
+
+        assert!(Operation::EqualToLiteral(st.clone())
+            .check(&Statement::EqualLiteral(ak.clone(), Value::from(1)))?);
+        assert!(!Operation::EqualToLiteral(st.clone())
+            .check(&Statement::EqualLiteral(ak.clone(), Value::from(2)))?);
+        let (ak_other, _) = value_of("other", 1);
+        assert!(!Operation::EqualToLiteral(st)
+            .check(&Statement::EqualLiteral(ak_other, Value::from(1)))?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_gt_lt_literal() -> Result<()> {
+        let (ak, st) = value_of("age", 30);
+
+        assert!(Operation::GtToLiteral(st.clone())
+            .check(&Statement::GtLiteral(ak.clone(), Value::from(18)))?);
+        assert!(!Operation::GtToLiteral(st.clone())
+            .check(&Statement::GtLiteral(ak.clone(), Value::from(30)))?);
+        assert!(Operation::LtToLiteral(st.clone())
+            .check(&Statement::LtLiteral(ak.clone(), Value::from(120)))?);
+        assert!(!Operation::LtToLiteral(st.clone())
+            .check(&Statement::LtLiteral(ak.clone(), Value::from(30)))?);
+        let (ak_other, _) = value_of("other", 30);
+        assert!(
+            !Operation::GtToLiteral(st).check(&Statement::GtLiteral(ak_other, Value::from(18)))?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_in_range() -> Result<()> {
+        let (ak, st) = value_of("age", 30);
+        let in_range = |min: i64, max: i64| {
+            Operation::InRangeFromEntries(st.clone()).check(&Statement::InRange(
+                ak.clone(),
+                Value::from(min),
+                Value::from(max),
+            ))
+        };
+
+        assert!(in_range(18, 120)?);
+        assert!(in_range(30, 30)?);
+        // below min
+        assert!(!in_range(31, 120)?);
+        // above max
+        assert!(!in_range(18, 29)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_equals_contained_value() -> Result<()> {
+        let dict = Dictionary::new(&HashMap::from([
+            (hash_str("alice"), Value::from(30)),
+            (hash_str("bob"), Value::from(40)),
+        ]));
+        let (ak1, st1) = value_of("age", 30);
+        let ak2 = AnchoredKey(SELF, hash_str("ages"));
+        let st2 = Statement::ValueOf(ak2.clone(), Value(dict.commitment().0));
+        let alice = Value(hash_str("alice").0);
+        let bob = Value(hash_str("bob").0);
+
+        let op = Operation::op_with_proof(
+            NativeOperation::EqualsContainedValue,
+            &[st1.clone(), st2.clone()],
+            dict.prove(&alice)?,
+        )?;
+        assert!(op.check(&Statement::EqualsContainedValue(
+            ak1.clone(),
+            ak2.clone(),
+            alice
+        ))?);
+        // The proof is for alice, whose value isn't the one at bob
+        assert!(matches!(
+            op.check(&Statement::EqualsContainedValue(
+                ak1.clone(),
+                ak2.clone(),
+                bob
+            )),
+            Err(OperationError::ProofInvalid(_))
+        ));
+        // The value at bob isn't 30
+        let op = Operation::EqualsContainedValue(st1, st2, dict.prove(&bob)?);
+        assert!(matches!(
+            op.check(&Statement::EqualsContainedValue(ak1, ak2, bob)),
+            Err(OperationError::ProofInvalid(_))
+        ));
+        // A proof is required
+        assert!(matches!(
+            Operation::op(NativeOperation::EqualsContainedValue, &op.args()),
+            Err(OperationError::ArityMismatch(_, 2))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename_contained_by() -> Result<()> {
+        let (ak1, _) = value_of("a", 1);
+        let (ak2, _) = value_of("b", 2);
+        let (ak3, _) = value_of("c", 1);
+
+        let op = Operation::op(
+            NativeOperation::RenameContainedBy,
+            &[
+                Statement::Contains(ak1.clone(), ak2.clone()),
+                Statement::Equal(ak1.clone(), ak3.clone()),
+            ],
+        )?;
+        assert!(op.check(&Statement::Contains(ak3.clone(), ak2.clone()))?);
+        assert!(!op.check(&Statement::Contains(ak1.clone(), ak2.clone()))?);
+
+        // The Equal statement may come normalized in either order
+        let op = Operation::op(
+            NativeOperation::RenameContainedBy,
+            &[
+                Statement::Contains(ak1.clone(), ak2.clone()),
+                Statement::Equal(ak3.clone(), ak1),
+            ],
+        )?;
+        assert!(op.check(&Statement::Contains(ak3, ak2))?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_transitive_equal_from_chain() -> Result<()> {
+        let aks = ["a", "b", "c", "d", "e"]
+            .iter()
+            .map(|k| value_of(k, 1).0)
+            .collect::<Vec<_>>();
+        let chain = aks
+            .windows(2)
+            .map(|w| Statement::Equal(w[0].clone(), w[1].clone()))
+            .collect::<Vec<_>>();
+
+        let op = Operation::op(NativeOperation::TransitiveEqualFromChain, &chain)?;
+        assert!(op.check(&Statement::Equal(aks[0].clone(), aks[4].clone()))?);
+        assert!(op.check(&Statement::Equal(aks[4].clone(), aks[0].clone()))?);
+        assert!(!op.check(&Statement::Equal(aks[0].clone(), aks[3].clone()))?);
+
+        // Links in either direction
+        let mut reversed = chain.clone();
+        reversed[1] = Statement::Equal(aks[2].clone(), aks[1].clone());
+        let op = Operation::op(NativeOperation::TransitiveEqualFromChain, &reversed)?;
+        assert!(op.check(&Statement::Equal(aks[0].clone(), aks[4].clone()))?);
+
+        // Broken chain: c == d is missing
+        let broken = vec![chain[0].clone(), chain[1].clone(), chain[3].clone()];
+        let op = Operation::op(NativeOperation::TransitiveEqualFromChain, &broken)?;
+        assert!(!op.check(&Statement::Equal(aks[0].clone(), aks[4].clone()))?);
+
+        // A chain needs at least two links
+        assert!(Operation::op(NativeOperation::TransitiveEqualFromChain, &chain[..1]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_div_mod_of() -> Result<()> {
+        let (ak1, st1) = value_of("a", 3);
+        let (ak2, st2) = value_of("b", 7);
+        let (ak3, st3) = value_of("c", 2);
+        let (ak4, st4) = value_of("d", 1);
+
+        // 3 == 7 / 2
+        let div = Statement::DivOf(ak1.clone(), ak2.clone(), ak3.clone());
+        assert!(Operation::DivOf(st1.clone(), st2.clone(), st3.clone()).check(&div)?);
+        // 1 == 7 % 2
+        let md = Statement::ModOf(ak4.clone(), ak2.clone(), ak3.clone());
+        assert!(Operation::ModOf(st4.clone(), st2.clone(), st3.clone()).check(&md)?);
+        // 1 != 7 / 2
+        let div_wrong = Statement::DivOf(ak4.clone(), ak2.clone(), ak3.clone());
+        assert!(!Operation::DivOf(st4.clone(), st2.clone(), st3.clone()).check(&div_wrong)?);
+
+        // Negative values use the signed i64 embedding: -3 == -7 / 2 and -1 == -7 % 2
+        let (ak5, st5) = value_of("e", -3);
+        let (ak6, st6) = value_of("f", -7);
+        let (ak7, st7) = value_of("g", -1);
+        assert!(
+            Operation::DivOf(st5, st6.clone(), st3.clone()).check(&Statement::DivOf(
+                ak5,
+                ak6.clone(),
+                ak3.clone()
+            ))?
+        );
+        assert!(
+            Operation::ModOf(st7, st6, st3.clone()).check(&Statement::ModOf(
+                ak7,
+                ak6,
+                ak3.clone()
+            ))?
+        );
+
+        // Division by zero
+        let (ak0, st0) = value_of("zero", 0);
+        assert!(matches!(
+            Operation::DivOf(st1.clone(), st2.clone(), st0.clone()).check(&Statement::DivOf(
+                ak1.clone(),
+                ak2.clone(),
+                ak0.clone()
+            )),
+            Err(OperationError::DeductionFailed(_))
+        ));
+        assert!(matches!(
+            Operation::ModOf(st1, st2, st0).check(&Statement::ModOf(ak1, ak2, ak0)),
+            Err(OperationError::DeductionFailed(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_and_or_not_of() -> Result<()> {
+        let bool_of = |key: &str, b: bool| value_of(key, b as i64);
+        for (b2, b3) in [(false, false), (false, true), (true, false), (true, true)] {
+            let (ak2, st2) = bool_of("b", b2);
+            let (ak3, st3) = bool_of("c", b3);
+            for b1 in [false, true] {
+                let (ak1, st1) = bool_of("a", b1);
+                assert_eq!(
+                    Operation::AndOf(st1.clone(), st2.clone(), st3.clone())
+                        .check(&Statement::AndOf(ak1.clone(), ak2.clone(), ak3.clone()))?,
+                    b1 == (b2 && b3)
+                );
+                assert_eq!(
+                    Operation::OrOf(st1.clone(), st2.clone(), st3.clone())
+                        .check(&Statement::OrOf(ak1.clone(), ak2.clone(), ak3.clone()))?,
+                    b1 == (b2 || b3)
+                );
+            }
         }
+        for b2 in [false, true] {
+            let (ak2, st2) = bool_of("b", b2);
+            for b1 in [false, true] {
+                let (ak1, st1) = bool_of("a", b1);
+                assert_eq!(
+                    Operation::NotOf(st1, st2.clone())
+                        .check(&Statement::NotOf(ak1, ak2.clone()))?,
+                    b1 == !b2
+                );
+            }
+        }
+
+        // Non-boolean operand
+        let (ak1, st1) = bool_of("a", true);
+        let (ak2, st2) = value_of("b", 2);
+        assert!(matches!(
+            Operation::NotOf(st1, st2).check(&Statement::NotOf(ak1, ak2)),
+            Err(OperationError::TypeMismatch(_))
+        ));
+
+        Ok(())
     }
 }
@@ -1,15 +1,21 @@
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use plonky2::field::types::Field;
+use plonky2::hash::poseidon::PoseidonHash;
+use plonky2::plonk::config::Hasher;
+use serde::{Deserialize, Serialize};
 use std::fmt;
-use strum_macros::FromRepr;
+use strum_macros::{EnumString, FromRepr};
 
-use super::{AnchoredKey, ToFields, Value, F};
+use super::{AnchoredKey, Hash, ToFields, Value, F};
+use crate::error::Pod2Error;
 
 pub const KEY_SIGNER: &str = "_signer";
+/// Key of the commitment to the `Array` of public keys of a pod signed by several signers
+pub const KEY_SIGNERS: &str = "_signers";
 pub const KEY_TYPE: &str = "_type";
 pub const STATEMENT_ARG_F_LEN: usize = 8;
 
-#[derive(Clone, Copy, Debug, FromRepr, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, EnumString, FromRepr, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NativeStatement {
     None = 0,
     ValueOf = 1,
@@ -22,6 +28,18 @@ pub enum NativeStatement {
     SumOf = 8,
     ProductOf = 9,
     MaxOf = 10,
+    Gteq = 11,
+    Lteq = 12,
+    EqualLiteral = 13,
+    DivOf = 14,
+    ModOf = 15,
+    AndOf = 16,
+    OrOf = 17,
+    NotOf = 18,
+    InRange = 19,
+    EqualsContainedValue = 20,
+    GtLiteral = 21,
+    LtLiteral = 22,
 }
 
 impl ToFields for NativeStatement {
@@ -32,7 +50,7 @@ impl ToFields for NativeStatement {
 
 // TODO: Incorporate custom statements into this enum.
 /// Type encapsulating statements with their associated arguments.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Statement {
     None,
     ValueOf(AnchoredKey, Value),
@@ -45,6 +63,21 @@ pub enum Statement {
     SumOf(AnchoredKey, AnchoredKey, AnchoredKey),
     ProductOf(AnchoredKey, AnchoredKey, AnchoredKey),
     MaxOf(AnchoredKey, AnchoredKey, AnchoredKey),
+    Gteq(AnchoredKey, AnchoredKey),
+    Lteq(AnchoredKey, AnchoredKey),
+    EqualLiteral(AnchoredKey, Value),
+    DivOf(AnchoredKey, AnchoredKey, AnchoredKey),
+    ModOf(AnchoredKey, AnchoredKey, AnchoredKey),
+    AndOf(AnchoredKey, AnchoredKey, AnchoredKey),
+    OrOf(AnchoredKey, AnchoredKey, AnchoredKey),
+    NotOf(AnchoredKey, AnchoredKey),
+    /// The value at the key is in the inclusive range `[min, max]`.
+    InRange(AnchoredKey, Value, Value),
+    /// The value at the first key equals the value stored under the literal key in the
+    /// dictionary at the second key.
+    EqualsContainedValue(AnchoredKey, AnchoredKey, Value),
+    GtLiteral(AnchoredKey, Value),
+    LtLiteral(AnchoredKey, Value),
 }
 
 impl Statement {
@@ -64,6 +97,18 @@ impl Statement {
             Self::SumOf(_, _, _) => NativeStatement::SumOf,
             Self::ProductOf(_, _, _) => NativeStatement::ProductOf,
             Self::MaxOf(_, _, _) => NativeStatement::MaxOf,
+            Self::Gteq(_, _) => NativeStatement::Gteq,
+            Self::Lteq(_, _) => NativeStatement::Lteq,
+            Self::EqualLiteral(_, _) => NativeStatement::EqualLiteral,
+            Self::DivOf(_, _, _) => NativeStatement::DivOf,
+            Self::ModOf(_, _, _) => NativeStatement::ModOf,
+            Self::AndOf(_, _, _) => NativeStatement::AndOf,
+            Self::OrOf(_, _, _) => NativeStatement::OrOf,
+            Self::NotOf(_, _) => NativeStatement::NotOf,
+            Self::InRange(_, _, _) => NativeStatement::InRange,
+            Self::EqualsContainedValue(_, _, _) => NativeStatement::EqualsContainedValue,
+            Self::GtLiteral(_, _) => NativeStatement::GtLiteral,
+            Self::LtLiteral(_, _) => NativeStatement::LtLiteral,
         }
     }
     pub fn args(&self) -> Vec<StatementArg> {
@@ -80,12 +125,59 @@ impl Statement {
             Self::SumOf(ak1, ak2, ak3) => vec![Key(ak1), Key(ak2), Key(ak3)],
             Self::ProductOf(ak1, ak2, ak3) => vec![Key(ak1), Key(ak2), Key(ak3)],
             Self::MaxOf(ak1, ak2, ak3) => vec![Key(ak1), Key(ak2), Key(ak3)],
+            Self::Gteq(ak1, ak2) => vec![Key(ak1), Key(ak2)],
+            Self::Lteq(ak1, ak2) => vec![Key(ak1), Key(ak2)],
+            Self::EqualLiteral(ak, v) => vec![Key(ak), Literal(v)],
+            Self::DivOf(ak1, ak2, ak3) => vec![Key(ak1), Key(ak2), Key(ak3)],
+            Self::ModOf(ak1, ak2, ak3) => vec![Key(ak1), Key(ak2), Key(ak3)],
+            Self::AndOf(ak1, ak2, ak3) => vec![Key(ak1), Key(ak2), Key(ak3)],
+            Self::OrOf(ak1, ak2, ak3) => vec![Key(ak1), Key(ak2), Key(ak3)],
+            Self::NotOf(ak1, ak2) => vec![Key(ak1), Key(ak2)],
+            Self::InRange(ak, min, max) => vec![Key(ak), Literal(min), Literal(max)],
+            Self::EqualsContainedValue(ak1, ak2, key) => vec![Key(ak1), Key(ak2), Literal(key)],
+            Self::GtLiteral(ak, v) => vec![Key(ak), Literal(v)],
+            Self::LtLiteral(ak, v) => vec![Key(ak), Literal(v)],
         }
     }
+    /// Canonical form of the statement: the keys of the symmetric predicates (`Equal`,
+    /// `NotEqual`) are sorted, so that `Equal(a, b)` and `Equal(b, a)` normalize to the same
+    /// statement.  Other statements are returned unchanged.
+    pub fn normalize(self) -> Self {
+        fn sorted(ak1: AnchoredKey, ak2: AnchoredKey) -> (AnchoredKey, AnchoredKey) {
+            if (ak2.0 .0, ak2.1) < (ak1.0 .0, ak1.1) {
+                (ak2, ak1)
+            } else {
+                (ak1, ak2)
+            }
+        }
+        match self {
+            Self::Equal(ak1, ak2) => {
+                let (ak1, ak2) = sorted(ak1, ak2);
+                Self::Equal(ak1, ak2)
+            }
+            Self::NotEqual(ak1, ak2) => {
+                let (ak1, ak2) = sorted(ak1, ak2);
+                Self::NotEqual(ak1, ak2)
+            }
+            st => st,
+        }
+    }
+    /// Commitment to the statement: the Poseidon hash of the field representation of its
+    /// normalized form.  Equivalent statements share the same commitment.
+    pub fn commitment(&self) -> Hash {
+        Hash(PoseidonHash::hash_no_pad(&self.clone().normalize().to_fields().0).elements)
+    }
 }
 
 impl ToFields for Statement {
     fn to_fields(self) -> (Vec<F>, usize) {
+        self.to_fields_ref()
+    }
+}
+
+impl Statement {
+    /// Same as [ToFields::to_fields], without consuming the statement.
+    pub fn to_fields_ref(&self) -> (Vec<F>, usize) {
         let (native_statement_f, native_statement_f_len) = self.code().to_fields();
         let (vec_statementarg_f, vec_statementarg_f_len) = self
             .args()
@@ -117,7 +209,7 @@ impl fmt::Display for Statement {
 }
 
 /// Statement argument type. Useful for statement decompositions.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum StatementArg {
     None,
     Literal(Value),
@@ -138,16 +230,22 @@ impl StatementArg {
     pub fn is_none(&self) -> bool {
         matches!(self, Self::None)
     }
-    pub fn literal(&self) -> Result<Value> {
+    pub fn literal(&self) -> Result<Value, Pod2Error> {
         match self {
             Self::Literal(value) => Ok(*value),
-            _ => Err(anyhow!("Statement argument {:?} is not a literal.", self)),
+            _ => Err(Pod2Error::InvalidStatementArg {
+                expected: "a literal",
+                arg: format!("{:?}", self),
+            }),
         }
     }
-    pub fn key(&self) -> Result<AnchoredKey> {
+    pub fn key(&self) -> Result<AnchoredKey, Pod2Error> {
         match self {
             Self::Key(ak) => Ok(ak.clone()),
-            _ => Err(anyhow!("Statement argument {:?} is not a key.", self)),
+            _ => Err(Pod2Error::InvalidStatementArg {
+                expected: "a key",
+                arg: format!("{:?}", self),
+            }),
         }
     }
 }
@@ -181,3 +279,120 @@ impl ToFields for StatementArg {
         (f, STATEMENT_ARG_F_LEN)
     }
 }
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::middleware::{hash_str, statements_to_fields, PodId, SELF};
+    use std::iter;
+
+    #[test]
+    fn test_statement_commitment() {
+        let ak1 = AnchoredKey(SELF, hash_str("a"));
+        let ak2 = AnchoredKey(SELF, hash_str("b"));
+
+        let st1 = Statement::Equal(ak1.clone(), ak2.clone());
+        let st2 = Statement::Equal(ak1.clone(), ak2.clone());
+        assert_eq!(st1.commitment(), st2.commitment());
+
+        // Equal is symmetric, Gt isn't
+        let st3 = Statement::Equal(ak2.clone(), ak1.clone());
+        assert_eq!(st1.commitment(), st3.commitment());
+        let gt = Statement::Gt(ak1.clone(), ak2.clone());
+        assert_ne!(
+            gt.commitment(),
+            Statement::Gt(ak2.clone(), ak1.clone()).commitment()
+        );
+
+        let st4 = Statement::NotEqual(ak1.clone(), ak2.clone());
+        let st5 = Statement::ValueOf(ak1, Value::from(1));
+        assert_ne!(st1.commitment(), st4.commitment());
+        assert_ne!(st1.commitment(), st5.commitment());
+    }
+
+    #[test]
+    fn test_statement_normalize() {
+        let ak1 = AnchoredKey(SELF, hash_str("a"));
+        let ak2 = AnchoredKey(SELF, hash_str("b"));
+
+        let eq = Statement::Equal(ak1.clone(), ak2.clone()).normalize();
+        assert_eq!(eq, Statement::Equal(ak2.clone(), ak1.clone()).normalize());
+        let ne = Statement::NotEqual(ak2.clone(), ak1.clone()).normalize();
+        assert_eq!(
+            ne,
+            Statement::NotEqual(ak1.clone(), ak2.clone()).normalize()
+        );
+        assert_eq!(eq.normalize(), eq);
+
+        let gt = Statement::Gt(ak2.clone(), ak1.clone());
+        assert_eq!(gt.clone().normalize(), gt);
+        let sum = Statement::SumOf(ak2.clone(), ak1.clone(), ak1);
+        assert_eq!(sum.clone().normalize(), sum);
+    }
+
+    #[test]
+    fn test_statement_serde() -> Result<()> {
+        let ak = |pod: &str, key: &str| AnchoredKey(PodId(hash_str(pod)), hash_str(key));
+        let (ak1, ak2, ak3) = (ak("a", "x"), ak("b", "y"), ak("c", "z"));
+        let statements = [
+            Statement::None,
+            Statement::ValueOf(ak1.clone(), Value::from(42)),
+            Statement::Equal(ak1.clone(), ak2.clone()),
+            Statement::NotEqual(ak1.clone(), ak2.clone()),
+            Statement::Gt(ak1.clone(), ak2.clone()),
+            Statement::Lt(ak1.clone(), ak2.clone()),
+            Statement::Contains(ak1.clone(), ak2.clone()),
+            Statement::NotContains(ak1.clone(), ak2.clone()),
+            Statement::SumOf(ak1.clone(), ak2.clone(), ak3.clone()),
+            Statement::ProductOf(ak1.clone(), ak2.clone(), ak3.clone()),
+            Statement::MaxOf(ak1.clone(), ak2.clone(), ak3.clone()),
+            Statement::Gteq(ak1.clone(), ak2.clone()),
+            Statement::Lteq(ak1.clone(), ak2.clone()),
+            Statement::EqualLiteral(ak1.clone(), Value::from(-1)),
+            Statement::DivOf(ak1.clone(), ak2.clone(), ak3.clone()),
+            Statement::ModOf(ak1.clone(), ak2.clone(), ak3.clone()),
+            Statement::AndOf(ak1.clone(), ak2.clone(), ak3.clone()),
+            Statement::OrOf(ak1.clone(), ak2.clone(), ak3.clone()),
+            Statement::NotOf(ak1.clone(), ak2.clone()),
+            Statement::InRange(ak1.clone(), Value::from(18), Value::from(120)),
+            Statement::EqualsContainedValue(ak1.clone(), ak2.clone(), Value::from(7)),
+            Statement::GtLiteral(ak1.clone(), Value::from(3)),
+            Statement::LtLiteral(ak1.clone(), Value::from(4)),
+        ];
+        for st in statements {
+            let json = serde_json::to_string(&st)?;
+            assert_eq!(serde_json::from_str::<Statement>(&json)?, st);
+            for arg in st.args() {
+                let json = serde_json::to_string(&arg)?;
+                assert_eq!(serde_json::from_str::<StatementArg>(&json)?, arg);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_statement_to_fields_ref() {
+        let ak1 = AnchoredKey(SELF, hash_str("a"));
+        let ak2 = AnchoredKey(PodId(hash_str("pod")), hash_str("b"));
+        let statements = [
+            Statement::None,
+            Statement::ValueOf(ak1.clone(), Value::from(7)),
+            Statement::Equal(ak1.clone(), ak2.clone()),
+            Statement::SumOf(ak1.clone(), ak2.clone(), ak1.clone()),
+        ];
+        for st in statements.iter() {
+            assert_eq!(st.to_fields_ref(), st.clone().to_fields());
+        }
+
+        let uncached = statements
+            .iter()
+            .flat_map(|st| {
+                let (fields, len) = st.clone().to_fields();
+                iter::once(F::from_canonical_usize(len)).chain(fields)
+            })
+            .collect::<Vec<_>>();
+        let cached = statements_to_fields(&statements);
+        assert_eq!(cached[0], F::from_canonical_usize(statements.len()));
+        assert_eq!(cached[1..], uncached);
+    }
+}
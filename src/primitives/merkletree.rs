@@ -2,7 +2,6 @@
 ///
 /// Current implementation is a wrapper on top of Plonky2's MerkleTree, but the future iteration
 /// will replace it by the MerkleTree specified at https://0xparc.github.io/pod2/merkletree.html .
-use anyhow::{anyhow, Result};
 use itertools::Itertools;
 use plonky2::field::types::Field;
 use plonky2::hash::{
@@ -16,6 +15,7 @@ use plonky2::plonk::config::Hasher;
 use std::collections::HashMap;
 use std::iter::IntoIterator;
 
+use crate::error::{Pod2Error, Result};
 use crate::middleware::{Hash, Value, C, D, F};
 
 const CAP_HEIGHT: usize = 0;
@@ -38,6 +38,7 @@ pub struct MerkleTree {
     leaves_map: HashMap<Hash, (Value, Value)>,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MerkleProof {
     existence: bool,
     index: usize,
@@ -89,11 +90,14 @@ impl MerkleTree {
 
     /// returns the value at the given key
     pub fn get(&self, key: &Value) -> Result<Value> {
-        let i = self.keyindex.get(&key).ok_or(anyhow!("key not in tree"))?;
+        let i = self
+            .keyindex
+            .get(&key)
+            .ok_or(Pod2Error::KeyNotFound(*key))?;
         let leaf_hash_raw = self.tree.get(*i);
         let leaf_hash_f: [F; 4] = leaf_hash_raw
             .try_into()
-            .map_err(|_| anyhow!("unexpected length (len!=4)"))?;
+            .map_err(|_| Pod2Error::InvalidLeafLength(leaf_hash_raw.len()))?;
         let leaf_hash: Hash = Hash(leaf_hash_f);
         let (_, value) = self.leaves_map.get(&leaf_hash).unwrap();
         Ok(*value)
@@ -107,7 +111,10 @@ impl MerkleTree {
     /// returns a proof of existence, which proves that the given key exists in
     /// the tree. It returns the `MerkleProof`.
     pub fn prove(&self, key: &Value) -> Result<MerkleProof> {
-        let i = self.keyindex.get(&key).ok_or(anyhow!("key not in tree"))?;
+        let i = self
+            .keyindex
+            .get(&key)
+            .ok_or(Pod2Error::KeyNotFound(*key))?;
         let proof = self.tree.prove(*i);
         Ok(MerkleProof {
             existence: true,
@@ -131,13 +138,61 @@ impl MerkleTree {
     /// verifies an inclusion proof for the given `key` and `value`
     pub fn verify(root: Hash, proof: &MerkleProof, key: &Value, value: &Value) -> Result<()> {
         if !proof.existence {
-            return Err(anyhow!(
-                "expected proof of existence, found proof of non-existence"
+            return Err(Pod2Error::ProofInvalid(
+                "expected proof of existence, found proof of non-existence".into(),
             ));
         }
         let leaf = PoseidonHash::hash_no_pad(&[key.0, value.0].concat()).elements;
         let root = HashOut::from_vec(root.0.to_vec());
         verify_merkle_proof(leaf.into(), proof.index, root, &proof.proof)
+            .map_err(|e| Pod2Error::ProofInvalid(e.to_string()))
+    }
+
+    /// verifies several inclusion proofs against the same `root`, computing each node shared
+    /// by their paths only once
+    pub fn verify_batch(root: Hash, proofs: &[(Value, Value, MerkleProof)]) -> Result<()> {
+        let root = HashOut::from_vec(root.0.to_vec());
+        // (level, index) -> node hash of the nodes already checked up to the root
+        let mut nodes: HashMap<(usize, usize), HashOut<F>> = HashMap::new();
+        for (key, value, proof) in proofs.iter().sorted_by_key(|(_, _, proof)| proof.index) {
+            if !proof.existence {
+                return Err(Pod2Error::ProofInvalid(
+                    "expected proof of existence, found proof of non-existence".into(),
+                ));
+            }
+            let mut node: HashOut<F> = PoseidonHash::hash_no_pad(&[key.0, value.0].concat())
+                .elements
+                .into();
+            let mut index = proof.index;
+            let mut shared = false;
+            for (level, sibling) in proof.proof.siblings.iter().enumerate() {
+                match nodes.get(&(level, index)) {
+                    Some(known) if *known == node => {
+                        shared = true;
+                        break;
+                    }
+                    Some(_) => {
+                        return Err(Pod2Error::ProofInvalid(
+                            "proof inconsistent with the batch".into(),
+                        ))
+                    }
+                    None => nodes.insert((level, index), node),
+                };
+                node = if index & 1 == 1 {
+                    PoseidonHash::two_to_one(*sibling, node)
+                } else {
+                    PoseidonHash::two_to_one(node, *sibling)
+                };
+                index >>= 1;
+            }
+            if !shared && node != root {
+                return Err(Pod2Error::ProofInvalid(format!(
+                    "root mismatch for key {:?}",
+                    key
+                )));
+            }
+        }
+        Ok(())
     }
 
     /// verifies a non-inclusion proof for the given `key`, that is, the given
@@ -145,8 +200,8 @@ impl MerkleTree {
     pub fn verify_nonexistence(_root: Hash, proof: &MerkleProof, _key: &Value) -> Result<()> {
         // mock method
         if proof.existence {
-            return Err(anyhow!(
-                "expected proof of non-existence, found proof of existence"
+            return Err(Pod2Error::ProofInvalid(
+                "expected proof of non-existence, found proof of existence".into(),
             ));
         }
         println!("WARNING: MerkleTree::verify_nonexistence is currently a mock");
@@ -212,4 +267,54 @@ pub mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_merkletree_verify_batch() -> Result<()> {
+        let kvs: HashMap<Value, Value> = (0..5)
+            .map(|i| {
+                (
+                    Value(hash_str(&format!("key_{}", i)).0),
+                    Value(hash_str(&format!("value_{}", i)).0),
+                )
+            })
+            .collect();
+        let tree = MerkleTree::new(&kvs);
+
+        let proofs = kvs
+            .iter()
+            .take(3)
+            .map(|(k, v)| Ok((*k, *v, tree.prove(k)?)))
+            .collect::<Result<Vec<_>>>()?;
+        MerkleTree::verify_batch(tree.root(), &proofs)?;
+
+        // a wrong value in one of the proofs makes the whole batch fail
+        let mut tampered = kvs
+            .iter()
+            .take(3)
+            .map(|(k, v)| Ok((*k, *v, tree.prove(k)?)))
+            .collect::<Result<Vec<_>>>()?;
+        tampered[1].1 = Value(hash_str("other").0);
+        assert!(MerkleTree::verify_batch(tree.root(), &tampered).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_merkletree_random() -> Result<()> {
+        let mut rng = rand::thread_rng();
+        let kvs: HashMap<Value, Value> = (0..100)
+            .map(|_| (Value::rand(&mut rng), Value::rand(&mut rng)))
+            .collect();
+        let tree = MerkleTree::new(&kvs);
+
+        for (k, v) in kvs.iter() {
+            assert_eq!(tree.get(k)?, *v);
+            let proof = tree.prove(k)?;
+            MerkleTree::verify(tree.root(), &proof, k, v)?;
+        }
+        let missing = Value(Hash::rand(&mut rng).0);
+        assert!(!tree.contains(&missing));
+
+        Ok(())
+    }
 }